@@ -4,48 +4,95 @@ use mazes::{
     grids::medium_rect_grid,
     units::{ColumnLength, RowLength},
 };
+use rand::{rngs::SmallRng, SeedableRng};
 
 fn bench_binary_maze_32_u16(c: &mut Criterion) {
     let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
 
     c.bench_function("binary_maze_32_u16", move |b| {
-        b.iter(|| generators::binary_tree(&mut g))
+        b.iter(|| generators::binary_tree(&mut g, &mut rng))
     });
 }
 
 fn bench_sidewinder_maze_32_u16(c: &mut Criterion) {
     let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
 
     c.bench_function("sidewinder_maze_32_u16", move |b| {
-        b.iter(|| generators::sidewinder(&mut g))
+        b.iter(|| generators::sidewinder(&mut g, &mut rng))
     });
 }
 
 fn bench_aldous_broder_maze_32_u16(c: &mut Criterion) {
     let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
     c.bench_function("aldous_broder_maze_32_u16", move |b| {
-        b.iter(|| generators::aldous_broder(&mut g, None))
+        b.iter(|| generators::aldous_broder(&mut g, None, &mut rng))
     });
 }
 
 fn bench_wilson_maze_32_u16(c: &mut Criterion) {
     let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
     c.bench_function("wilson_maze_32_u16", move |b| {
-        b.iter(|| generators::wilson(&mut g, None))
+        b.iter(|| generators::wilson(&mut g, None, &mut rng))
     });
 }
 
 fn bench_hunt_and_kill_maze_32_u16(c: &mut Criterion) {
     let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
     c.bench_function("hunt_and_kill_maze_32_u16", move |b| {
-        b.iter(|| generators::hunt_and_kill(&mut g, None))
+        b.iter(|| generators::hunt_and_kill(&mut g, None, &mut rng))
     });
 }
 
 fn bench_recursive_backtracker_maze_32_u16(c: &mut Criterion) {
     let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
     c.bench_function("recursive_backtracker_maze_32_u16", move |b| {
-        b.iter(|| generators::recursive_backtracker(&mut g, None))
+        b.iter(|| generators::recursive_backtracker(&mut g, None, &mut rng))
+    });
+}
+
+fn bench_simplified_prims_maze_32_u16(c: &mut Criterion) {
+    let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
+    c.bench_function("simplified_prims_maze_32_u16", move |b| {
+        b.iter(|| generators::simplified_prims(&mut g, None, &mut rng))
+    });
+}
+
+fn bench_true_prims_maze_32_u16(c: &mut Criterion) {
+    let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
+    c.bench_function("true_prims_maze_32_u16", move |b| {
+        b.iter(|| generators::true_prims(&mut g, None, &mut rng))
+    });
+}
+
+fn bench_kruskal_maze_32_u16(c: &mut Criterion) {
+    let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
+    c.bench_function("kruskal_maze_32_u16", move |b| {
+        b.iter(|| generators::kruskal(&mut g, None, &mut rng))
+    });
+}
+
+fn bench_ellers_maze_32_u16(c: &mut Criterion) {
+    let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
+    c.bench_function("ellers_maze_32_u16", move |b| {
+        b.iter(|| generators::ellers(&mut g, &mut rng))
+    });
+}
+
+fn bench_recursive_division_maze_32_u16(c: &mut Criterion) {
+    let mut g = medium_rect_grid(RowLength(32), ColumnLength(32)).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
+    c.bench_function("recursive_division_maze_32_u16", move |b| {
+        b.iter(|| generators::recursive_division(&mut g, 0, &mut rng))
     });
 }
 
@@ -56,6 +103,11 @@ criterion_group!(
     bench_aldous_broder_maze_32_u16,
     bench_wilson_maze_32_u16,
     bench_hunt_and_kill_maze_32_u16,
-    bench_recursive_backtracker_maze_32_u16
+    bench_recursive_backtracker_maze_32_u16,
+    bench_simplified_prims_maze_32_u16,
+    bench_true_prims_maze_32_u16,
+    bench_kruskal_maze_32_u16,
+    bench_ellers_maze_32_u16,
+    bench_recursive_division_maze_32_u16
 );
 criterion_main!(benches);
@@ -9,9 +9,8 @@ const MASK_IMAGE_PATH: &str = "resources/mask-60x60.png";
 const FULLMASK_IMAGE_PATH: &str = "resources/mask-all-60x60.png";
 
 fn open_test_image(file_path_str: &str) -> DynamicImage {
-    let img = image::open(&Path::new(file_path_str))
-        .expect(format!("Unable to open and read test mask image file {}", file_path_str).as_ref());
-    img
+    image::open(Path::new(file_path_str))
+        .unwrap_or_else(|_| panic!("Unable to open and read test mask image file {}", file_path_str))
 }
 
 fn load_binary_mask() -> BinaryMask2D {
@@ -4,13 +4,14 @@ use mazes::generators;
 use mazes::grids::large_rect_grid;
 use mazes::pathing;
 use mazes::units::{ColumnLength, RowLength};
+use rand::{rngs::SmallRng, SeedableRng};
 
 type SquareCellDistances = pathing::Distances<SquareCell, u32>;
 
 fn bench_distances(c: &mut Criterion) {
     c.bench_function("distances", |b| {
         let mut g = large_rect_grid(RowLength(350), ColumnLength(350)).unwrap();
-        generators::recursive_backtracker(&mut g, None);
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(0));
         let start_coord = Cartesian2DCoordinate::new(250, 250);
         b.iter(|| SquareCellDistances::for_grid(&g, start_coord))
     });
@@ -19,7 +20,7 @@ fn bench_distances(c: &mut Criterion) {
 fn bench_furthest_points(c: &mut Criterion) {
     c.bench_function("furthest_points", |b| {
         let mut g = large_rect_grid(RowLength(350), ColumnLength(350)).unwrap();
-        generators::recursive_backtracker(&mut g, None);
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(0));
         let start_coord = Cartesian2DCoordinate::new(250, 250);
         let distances = SquareCellDistances::for_grid(&g, start_coord).unwrap();
         b.iter(|| distances.furthest_points_on_grid())
@@ -29,7 +30,7 @@ fn bench_furthest_points(c: &mut Criterion) {
 fn bench_shortest_path(c: &mut Criterion) {
     c.bench_function("shortest_path", |b| {
         let mut g = large_rect_grid(RowLength(350), ColumnLength(350)).unwrap();
-        generators::recursive_backtracker(&mut g, None);
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(0));
         let start_coord = Cartesian2DCoordinate::new(250, 250);
         let distances = SquareCellDistances::for_grid(&g, start_coord).unwrap();
         let end_coord = Cartesian2DCoordinate::new(0, 0);
@@ -37,5 +38,21 @@ fn bench_shortest_path(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_distances, bench_furthest_points, bench_shortest_path);
+fn bench_astar_manhattan(c: &mut Criterion) {
+    c.bench_function("astar_manhattan", |b| {
+        let mut g = large_rect_grid(RowLength(350), ColumnLength(350)).unwrap();
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(0));
+        let start_coord = Cartesian2DCoordinate::new(250, 250);
+        let end_coord = Cartesian2DCoordinate::new(0, 0);
+        b.iter(|| pathing::astar(&g, start_coord, end_coord, &pathing::Manhattan))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_distances,
+    bench_furthest_points,
+    bench_shortest_path,
+    bench_astar_manhattan
+);
 criterion_main!(benches);
@@ -6,6 +6,7 @@ use mazes::grids::large_rect_grid;
 use mazes::pathing;
 use mazes::renderers;
 use mazes::units::{ColumnLength, RowLength};
+use rand::{rngs::SmallRng, SeedableRng};
 
 type SquareCellDistances = pathing::Distances<SquareCell, u32>;
 
@@ -13,7 +14,7 @@ fn render_grid(c: &mut Criterion) {
     c.bench_function("render_grid", |b| {
         let mut maze_grid = large_rect_grid(RowLength(200), ColumnLength(200)).unwrap();
         let start_coord = Cartesian2DCoordinate::new(0, 0);
-        generators::recursive_backtracker(&mut maze_grid, None);
+        generators::recursive_backtracker(&mut maze_grid, None, &mut SmallRng::seed_from_u64(0));
         let distances = SquareCellDistances::for_grid(&maze_grid, start_coord);
 
         let render_options = renderers::RenderOptionsBuilder::new()
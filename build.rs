@@ -6,7 +6,12 @@ use std::path::Path;
 use walkdir::{DirEntry, WalkDir};
 
 fn main() {
-    // Assume libsdl2*-dev is installed on BSD, but the link search path may not include the directory
+    // Nothing to link unless building with the sdl feature.
+    if env::var_os("CARGO_FEATURE_SDL").is_none() {
+        return;
+    }
+
+    // Assume libsdl2-dev is installed on BSD, but the link search path may not include the directory
     // containing the libs.
     if cfg!(any(
         target_os = "freebsd",
@@ -17,7 +22,7 @@ fn main() {
         println!("cargo:rustc-link-search=/usr/local/lib");
     }
 
-    // Assume libsdl2*-dev etc. are installed with the package manager on unix family systems but on
+    // Assume libsdl2-dev is installed with the package manager on unix family systems but on
     // a windows OS we look for the C built sdl2 libraries for the relevant platform/architecture to be
     // provided in a sub-directory and add them to the Link arguments.
     // We also ensure that the DLLs have been copied to the project root (or SDL_DLLS_RUN_DIR env var dir)
@@ -48,11 +53,7 @@ fn main() {
             dir
         };
 
-        let machine_lib_dirs = [
-            select_libs_dir("sdl2"),
-            select_libs_dir("sdl2-image"),
-            select_libs_dir("sdl2-ttf"),
-        ];
+        let machine_lib_dirs = [select_libs_dir("sdl2")];
 
         for dir in &machine_lib_dirs {
             println!("cargo:rustc-flags=-L {}", dir);
@@ -1,9 +1,10 @@
 use crate::{
-    grid_traits::GridDimensions,
-    units::{ColumnIndex, RowIndex, RowLength},
+    grid_traits::{GridDimensions, GridLinks},
+    units::{ColumnIndex, ColumnsCount, NodesCount, RowIndex, RowLength, RowsCount},
 };
 
 use rand::{rngs::SmallRng, Rng};
+use serde_derive::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::{
     convert::From,
@@ -43,6 +44,66 @@ pub trait Cell {
         dimensions: &dyn GridDimensions,
     ) -> Option<Self::Coord>;
 
+    /// Does moving 1 cell away from `coord` in the given direction wrap around to the other side of the grid,
+    /// e.g. going clockwise from the last cell of a polar grid ring back to the first cell.
+    fn is_wrapping_offset(_coord: Self::Coord, _dir: Self::Direction, _dimensions: &dyn GridDimensions) -> bool {
+        false
+    }
+
+    /// The cells that the cell could be linked to by a passage. The default is the adjacent cell in each direction,
+    /// cell types whose neighbours depend on the passages already carved, e.g. weave cells, override it.
+    fn neighbours(coord: Self::Coord, grid: &dyn GridLinks<Self>) -> Self::CoordinateSmallVec
+    where
+        Self: Sized,
+    {
+        Self::offset_directions(Some(coord), grid.dimensions())
+            .iter()
+            .filter_map(|dir| grid.neighbour_at_direction(coord, *dir))
+            .collect()
+    }
+
+    /// One of the cell's neighbours at random, or None if the pick has no neighbour, e.g. heading off the edge of
+    /// the grid. The default picks a random direction.
+    fn random_neighbour(coord: Self::Coord, grid: &dyn GridLinks<Self>, rng: &mut SmallRng) -> Option<Self::Coord>
+    where
+        Self: Sized,
+    {
+        grid.neighbour_at_direction(coord, Self::rand_direction(rng, grid.dimensions(), coord))
+    }
+
+    /// Can the link between two cells be removed, e.g. to rebuild a wall? Always, the default, unless the cell type
+    /// needs the link to hold the maze together, e.g. the corridor over a weave cell's tunnel.
+    fn can_unlink(_a: Self::Coord, _b: Self::Coord, _grid: &dyn GridLinks<Self>) -> bool
+    where
+        Self: Sized,
+    {
+        true
+    }
+
+    /// The directions the binary tree generator can link the cell in, given the roughly vertical and roughly
+    /// horizontal directions chosen for the whole maze. The default, both of them, gives a perfect maze when every
+    /// cell but one has a neighbour in at least one of them.
+    fn binary_tree_directions(
+        _coord: Self::Coord,
+        vertical: Self::Direction,
+        horizontal: Self::Direction,
+        _dimensions: &dyn GridDimensions,
+    ) -> Self::DirectionSmallVec {
+        [vertical, horizontal].iter().cloned().collect()
+    }
+
+    /// The distances along x and y of `Coordinate::as_cartesian_2d` that a single link can move, e.g. (2, 0) for weave
+    /// cells tunnelling under a neighbour. `pathing::astar` scales its estimates by the longest of these.
+    fn link_offsets() -> &'static [(u32, u32)] {
+        &[(1, 0), (0, 1)]
+    }
+
+    /// Whether x measures the same distance on every row, false for polar cells where each row has its own number of
+    /// cells. Otherwise only the distance between rows can be estimated.
+    fn columns_align_across_rows() -> bool {
+        true
+    }
+
     fn rand_direction(rng: &mut SmallRng, dimensions: &dyn GridDimensions, from: Self::Coord) -> Self::Direction;
     fn rand_roughly_vertical_direction(
         rng: &mut SmallRng,
@@ -56,7 +117,7 @@ pub trait Cell {
     ) -> Self::Direction;
 }
 
-#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug, Ord, PartialOrd)]
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct Cartesian2DCoordinate {
     pub x: u32,
     pub y: u32,
@@ -164,11 +225,30 @@ impl Cartesian2DCoordinate {
 impl Coordinate for Cartesian2DCoordinate {
     #[inline]
     fn from_row_major_index(index: usize, data: &dyn GridDimensions) -> Cartesian2DCoordinate {
-        let RowLength(width) = data.row_length(None).expect("invalid row index"); // todo fix up for Polar mazes
-        let x = index % width;
-        let y = index / width;
+        if let Some(RowLength(width)) = data.row_length(None) {
+            let x = index % width;
+            let y = index / width;
 
-        Cartesian2DCoordinate::new(x as u32, y as u32)
+            Cartesian2DCoordinate::new(x as u32, y as u32)
+        } else {
+            // The rows vary in length (e.g. polar grid rings), so binary search for the row containing the index.
+            let nodes_before_row = |row| {
+                let NodesCount(count) = data.nodes_count_up_to(RowIndex(row)).expect("invalid row index");
+                count
+            };
+            let RowsCount(rows) = data.rows();
+            let (mut low, mut high) = (0, rows);
+            while high - low > 1 {
+                let mid = low + (high - low) / 2;
+                if nodes_before_row(mid) <= index {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+
+            Cartesian2DCoordinate::new((index - nodes_before_row(low)) as u32, low as u32)
+        }
     }
 
     #[inline]
@@ -200,6 +280,16 @@ pub enum ClockDirection {
     Outward(u8),
 }
 
+impl PolarCell {
+    /// How many cells on the next row out from `row` share a boundary with each cell on `row`.
+    /// Returns None if `row` is the outermost row or beyond.
+    fn outward_ratio(row: u32, dimensions: &dyn GridDimensions) -> Option<u32> {
+        let RowLength(row_len) = dimensions.row_length(Some(RowIndex(row as usize)))?;
+        let RowLength(next_len) = dimensions.row_length(Some(RowIndex(row as usize + 1)))?;
+        Some((next_len / row_len) as u32)
+    }
+}
+
 impl Cell for PolarCell {
     type Coord = Cartesian2DCoordinate;
     type Direction = ClockDirection;
@@ -223,24 +313,19 @@ impl Cell for PolarCell {
 
         if let Some(c) = coord {
             let y = c.y;
-            let next_row_length = dimensions.row_length(Some(RowIndex(y as usize + 1)));
-
-            if let Some(RowLength(next_len)) = next_row_length {
-                let RowLength(row_len) = dimensions
-                    .row_length(Some(RowIndex(y as usize)))
-                    .expect("Invalid current row index");
-                let ratio = next_len / row_len;
 
+            if let Some(RowLength(row_len)) = dimensions.row_length(Some(RowIndex(y as usize))) {
                 let mut dirs = Self::DirectionSmallVec::new();
-                let basic_dirs = [
-                    ClockDirection::Clockwise,
-                    ClockDirection::CounterClockwise,
-                    ClockDirection::Inward,
-                    ClockDirection::Outward(0),
-                ];
-                dirs.extend(basic_dirs.iter().cloned());
-                for i in 1..(ratio - 1) {
-                    dirs.push(ClockDirection::Outward(i as u8));
+
+                // The centre cell is a row of its own with nothing clockwise, counter clockwise or inward.
+                if row_len > 1 {
+                    dirs.extend([ClockDirection::Clockwise, ClockDirection::CounterClockwise]);
+                }
+                if y > 0 {
+                    dirs.push(ClockDirection::Inward);
+                }
+                if let Some(ratio) = Self::outward_ratio(y, dimensions) {
+                    dirs.extend((0..ratio).map(|n| ClockDirection::Outward(n as u8)));
                 }
 
                 dirs
@@ -255,6 +340,7 @@ impl Cell for PolarCell {
 
     /// Creates a new `Coord` offset 1 cell away in the given direction.
     /// Returns None if the Coordinate is not representable.
+    /// Moving clockwise or counter clockwise wraps around the ring.
     fn offset_coordinate(
         coord: Self::Coord,
         dir: Self::Direction,
@@ -264,68 +350,75 @@ impl Cell for PolarCell {
             let c2d = coord.as_cartesian_2d();
             (c2d.x, c2d.y)
         };
+        let RowLength(row_len) = dimensions.row_length(Some(RowIndex(y as usize)))?;
+        let row_len = row_len as u32;
 
         match dir {
-            // left or right, adjust x coord. Should we care about boundaries and disallow wrap around?
-            // some algorithms may need it to terminate?
-            ClockDirection::Clockwise => Some(Self::Coord::new(x + 1, y)),
+            ClockDirection::Clockwise => {
+                if row_len > 1 {
+                    Some(Self::Coord::new((x + 1) % row_len, y))
+                } else {
+                    None
+                }
+            }
 
-            ClockDirection::CounterClockwise => Some(Self::Coord::new(x - 1, y)),
+            ClockDirection::CounterClockwise => {
+                if row_len > 1 {
+                    Some(Self::Coord::new(if x == 0 { row_len - 1 } else { x - 1 }, y))
+                } else {
+                    None
+                }
+            }
 
             ClockDirection::Inward => {
                 if y != 0 {
-                    if y != 1 {
-                        let RowLength(row_len) = dimensions
-                            .row_length(Some(RowIndex(y as usize)))
-                            .expect("Invalid current row index");
-                        let RowLength(prev_row_length) = dimensions
-                            .row_length(Some(RowIndex(y as usize - 1)))
-                            .expect("Invalid prev row index");
-
-                        if row_len == prev_row_length {
-                            Some(Self::Coord::new(x, y - 1))
-                        } else {
-                            let ratio = row_len / prev_row_length;
-                            let inward_x = x / ratio as u32;
-                            Some(Self::Coord::new(inward_x, y - 1))
-                        }
-                    } else {
-                        // Go from row 1 to row 0 which has only one cell
-                        Some(Self::Coord::new(0, 0))
-                    }
+                    let RowLength(prev_row_length) = dimensions
+                        .row_length(Some(RowIndex(y as usize - 1)))
+                        .expect("Invalid prev row index");
+                    let ratio = row_len / prev_row_length as u32;
+                    Some(Self::Coord::new(x / ratio, y - 1))
                 } else {
                     // There is no row inward of row zero that is representable as a coordinate
                     None
                 }
             }
+
             ClockDirection::Outward(n) => {
-                let RowLength(row_len) = dimensions
-                    .row_length(Some(RowIndex(y as usize)))
-                    .expect("Invalid current row index");
-                let next_row_length = dimensions.row_length(Some(RowIndex(y as usize + 1)));
-                if let Some(RowLength(next_len)) = next_row_length {
-                    let ratio = next_len / row_len;
-                    let outward_x = (x * ratio as u32) + u32::from(n);
-                    Some(Self::Coord::new(outward_x, y + 1))
+                // None if gone passed the outermost rim of the circle
+                let ratio = Self::outward_ratio(y, dimensions)?;
+                if u32::from(n) < ratio {
+                    Some(Self::Coord::new((x * ratio) + u32::from(n), y + 1))
                 } else {
-                    // Gone passed the outermost rim of the circle - will be invalid
-                    Some(Self::Coord::new(x + u32::from(n), y + 1))
+                    None
                 }
             }
         }
     }
 
-    fn rand_direction(rng: &mut SmallRng, _: &dyn GridDimensions, _: Self::Coord) -> Self::Direction {
-        const DIRS_COUNT: usize = 5;
-        const DIRS: [ClockDirection; DIRS_COUNT] = [
-            ClockDirection::Clockwise,
-            ClockDirection::CounterClockwise,
-            ClockDirection::Inward,
-            ClockDirection::Outward(0),
-            ClockDirection::Outward(1),
-        ]; // (1) not always valid
-        let dir_index = rng.gen::<usize>() % DIRS_COUNT;
-        DIRS[dir_index]
+    fn is_wrapping_offset(coord: Self::Coord, dir: Self::Direction, dimensions: &dyn GridDimensions) -> bool {
+        let row_len = dimensions
+            .row_length(Some(RowIndex(coord.y as usize)))
+            .map_or(0, |RowLength(len)| len as u32);
+        match dir {
+            ClockDirection::Clockwise => row_len > 1 && coord.x == row_len - 1,
+            ClockDirection::CounterClockwise => row_len > 1 && coord.x == 0,
+            _ => false,
+        }
+    }
+
+    fn columns_align_across_rows() -> bool {
+        false
+    }
+
+    fn rand_direction(rng: &mut SmallRng, dimensions: &dyn GridDimensions, from: Self::Coord) -> Self::Direction {
+        // The number of outward directions varies by row, so pick from what is actually available.
+        let dirs = Self::offset_directions(Some(from), dimensions);
+        if dirs.is_empty() {
+            // A grid with only the centre cell, there is nowhere to go.
+            ClockDirection::Inward
+        } else {
+            dirs[rng.gen::<usize>() % dirs.len()]
+        }
     }
 
     fn rand_roughly_vertical_direction(
@@ -340,17 +433,432 @@ impl Cell for PolarCell {
         }
     }
 
+    /// Always inward. Every cell has exactly one inward neighbour, whereas picking an outward direction would
+    /// leave the outermost row with no way to carve a passage other than around the ring.
+    fn rand_roughly_horizontal_direction(
+        _: &mut SmallRng,
+        _: &dyn GridDimensions,
+        _: Option<Self::Coord>,
+    ) -> Self::Direction {
+        ClockDirection::Inward
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum CompassHex {
+    North,
+    South,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+/// A flat topped hexagon.
+///
+/// Hexagons are laid out in columns, with the odd columns shifted half a cell down from the even columns,
+/// so a cell's diagonal neighbours are on the same row or the one above/below depending on its column.
+#[derive(Copy, Clone, Debug)]
+pub struct HexCell;
+
+impl Cell for HexCell {
+    type Coord = Cartesian2DCoordinate;
+    type Direction = CompassHex;
+    type CoordinateSmallVec = SmallVec<[Self::Coord; 6]>;
+    type CoordinateOptionSmallVec = SmallVec<[Option<Self::Coord>; 6]>;
+    type DirectionSmallVec = SmallVec<[CompassHex; 6]>;
+
+    fn offset_directions(_: Option<Self::Coord>, _: &dyn GridDimensions) -> Self::DirectionSmallVec {
+        [
+            CompassHex::North,
+            CompassHex::South,
+            CompassHex::NorthEast,
+            CompassHex::NorthWest,
+            CompassHex::SouthEast,
+            CompassHex::SouthWest,
+        ]
+        .iter()
+        .cloned()
+        .collect::<Self::DirectionSmallVec>()
+    }
+
+    fn offset_coordinate(coord: Self::Coord, dir: Self::Direction, _: &dyn GridDimensions) -> Option<Self::Coord> {
+        let (x, y) = (coord.x, coord.y);
+        let is_even_column = x % 2 == 0;
+        // Rows of the diagonal neighbours either side of this cell.
+        let north_diagonal_y = if is_even_column { y.checked_sub(1) } else { Some(y) };
+        let south_diagonal_y = if is_even_column { y } else { y + 1 };
+
+        match dir {
+            CompassHex::North => y.checked_sub(1).map(|north_y| Cartesian2DCoordinate::new(x, north_y)),
+            CompassHex::South => Some(Cartesian2DCoordinate::new(x, y + 1)),
+            CompassHex::NorthEast => north_diagonal_y.map(|north_y| Cartesian2DCoordinate::new(x + 1, north_y)),
+            CompassHex::NorthWest => match (x.checked_sub(1), north_diagonal_y) {
+                (Some(west_x), Some(north_y)) => Some(Cartesian2DCoordinate::new(west_x, north_y)),
+                _ => None,
+            },
+            CompassHex::SouthEast => Some(Cartesian2DCoordinate::new(x + 1, south_diagonal_y)),
+            CompassHex::SouthWest => x
+                .checked_sub(1)
+                .map(|west_x| Cartesian2DCoordinate::new(west_x, south_diagonal_y)),
+        }
+    }
+
+    fn link_offsets() -> &'static [(u32, u32)] {
+        &[(1, 0), (0, 1), (1, 1)]
+    }
+
+    fn rand_direction(rng: &mut SmallRng, _: &dyn GridDimensions, _: Self::Coord) -> Self::Direction {
+        const DIRS_COUNT: usize = 6;
+        const DIRS: [CompassHex; DIRS_COUNT] = [
+            CompassHex::North,
+            CompassHex::South,
+            CompassHex::NorthEast,
+            CompassHex::NorthWest,
+            CompassHex::SouthEast,
+            CompassHex::SouthWest,
+        ];
+        let dir_index = rng.gen::<usize>() % DIRS_COUNT;
+        DIRS[dir_index]
+    }
+
+    /// Always north. The roughly horizontal directions are then the southern diagonals, so that every cell
+    /// outside the first row or column has a neighbour in one of the two directions.
+    fn rand_roughly_vertical_direction(
+        _: &mut SmallRng,
+        _: &dyn GridDimensions,
+        _: Option<Self::Coord>,
+    ) -> Self::Direction {
+        CompassHex::North
+    }
+
     fn rand_roughly_horizontal_direction(
         rng: &mut SmallRng,
         _: &dyn GridDimensions,
         _: Option<Self::Coord>,
     ) -> Self::Direction {
         if rng.gen() {
-            ClockDirection::Inward
+            CompassHex::SouthEast
+        } else {
+            CompassHex::SouthWest
+        }
+    }
+}
+
+/// A triangle, pointing alternately up and down along each row and column.
+///
+/// A cell is upright when the sum of its x and y coordinates is even. An upright triangle has a flat southern
+/// side and no northern neighbour, a downward pointing triangle has a flat northern side and no southern neighbour.
+/// All triangles have east and west neighbours.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct TriangleCell;
+
+impl TriangleCell {
+    #[inline]
+    pub fn is_upright(coord: Cartesian2DCoordinate) -> bool {
+        (coord.x + coord.y) & 1 == 0
+    }
+
+    /// The neighbouring coordinate in the given direction, if it is on the grid.
+    fn neighbour(
+        coord: Cartesian2DCoordinate,
+        dir: CompassPrimary,
+        dimensions: &dyn GridDimensions,
+    ) -> Option<Cartesian2DCoordinate> {
+        let (ColumnsCount(columns), RowsCount(rows)) = (dimensions.columns(), dimensions.rows());
+        Self::offset_coordinate(coord, dir, dimensions).filter(|c| (c.x as usize) < columns && (c.y as usize) < rows)
+    }
+
+    fn directions_from(coord: Cartesian2DCoordinate) -> [CompassPrimary; 3] {
+        if Self::is_upright(coord) {
+            [CompassPrimary::West, CompassPrimary::East, CompassPrimary::South]
+        } else {
+            [CompassPrimary::West, CompassPrimary::East, CompassPrimary::North]
+        }
+    }
+}
+
+impl Cell for TriangleCell {
+    type Coord = Cartesian2DCoordinate;
+    type Direction = CompassPrimary;
+    type CoordinateSmallVec = SmallVec<[Self::Coord; 3]>;
+    type CoordinateOptionSmallVec = SmallVec<[Option<Self::Coord>; 3]>;
+    type DirectionSmallVec = SmallVec<[CompassPrimary; 4]>;
+
+    fn offset_directions(coord: Option<Self::Coord>, _: &dyn GridDimensions) -> Self::DirectionSmallVec {
+        if let Some(c) = coord {
+            Self::directions_from(c).iter().cloned().collect()
+        } else {
+            [
+                CompassPrimary::North,
+                CompassPrimary::South,
+                CompassPrimary::East,
+                CompassPrimary::West,
+            ]
+            .iter()
+            .cloned()
+            .collect()
+        }
+    }
+
+    /// Only half the triangles have a neighbour in the vertical direction, so a triangle at the end of a row in the
+    /// horizontal direction that points the other way has neither neighbour. Instead it links back along its row to
+    /// a triangle that always links vertically, keeping the maze one tree.
+    fn binary_tree_directions(
+        coord: Self::Coord,
+        vertical: Self::Direction,
+        horizontal: Self::Direction,
+        dimensions: &dyn GridDimensions,
+    ) -> Self::DirectionSmallVec {
+        let backwards = match horizontal {
+            CompassPrimary::East => CompassPrimary::West,
+            _ => CompassPrimary::East,
+        };
+        let has_neighbour = |c, dir| Self::neighbour(c, dir, dimensions).is_some();
+        let links_back_to = |c| !has_neighbour(c, vertical) && !has_neighbour(c, horizontal);
+
+        if has_neighbour(coord, vertical) {
+            match Self::neighbour(coord, horizontal, dimensions) {
+                Some(next) if links_back_to(next) => [vertical].iter().cloned().collect(),
+                _ => [vertical, horizontal].iter().cloned().collect(),
+            }
+        } else if has_neighbour(coord, horizontal) {
+            [horizontal].iter().cloned().collect()
+        } else {
+            // Only the root of the tree, at the end of the last row in the vertical direction, links nowhere.
+            Self::neighbour(coord, backwards, dimensions)
+                .filter(|previous| has_neighbour(*previous, vertical))
+                .map(|_| backwards)
+                .into_iter()
+                .collect()
+        }
+    }
+
+    /// Returns None for a north offset from an upright triangle or a south offset from a downward triangle.
+    fn offset_coordinate(
+        coord: Self::Coord,
+        dir: Self::Direction,
+        dimensions: &dyn GridDimensions,
+    ) -> Option<Self::Coord> {
+        let is_upright = Self::is_upright(coord);
+        match dir {
+            CompassPrimary::North if is_upright => None,
+            CompassPrimary::South if !is_upright => None,
+            _ => SquareCell::offset_coordinate(coord, dir, dimensions),
+        }
+    }
+
+    fn rand_direction(rng: &mut SmallRng, _: &dyn GridDimensions, from: Self::Coord) -> Self::Direction {
+        let dirs = Self::directions_from(from);
+        dirs[rng.gen::<usize>() % dirs.len()]
+    }
+
+    fn rand_roughly_vertical_direction(
+        rng: &mut SmallRng,
+        dimensions: &dyn GridDimensions,
+        from: Option<Self::Coord>,
+    ) -> Self::Direction {
+        SquareCell::rand_roughly_vertical_direction(rng, dimensions, from)
+    }
+
+    fn rand_roughly_horizontal_direction(
+        rng: &mut SmallRng,
+        dimensions: &dyn GridDimensions,
+        from: Option<Self::Coord>,
+    ) -> Self::Direction {
+        SquareCell::rand_roughly_horizontal_direction(rng, dimensions, from)
+    }
+}
+
+/// A square cell of a weave maze, where passages can tunnel under perpendicular corridors.
+///
+/// The hidden "under" cell beneath a crossing is not a separate node of the grid, it is the link between the two
+/// cells either side of the crossed corridor, which are two cells apart. A cell is only tunnelled under when it is a
+/// straight corridor linked on both sides across the tunnel, and each cell can have at most one tunnel beneath it.
+///
+/// `Grid::neighbours` reports the cell beyond a corridor as a neighbour when a tunnel to it is possible, so the
+/// generators that carve to neighbours, e.g. recursive backtracker, hunt and kill and growing tree, weave crossings.
+/// The row by row generators, e.g. binary tree and sidewinder, never do. A tunnel's corridor is never unlinked.
+#[derive(Copy, Clone, Debug)]
+pub struct WeaveCell;
+
+impl Cell for WeaveCell {
+    type Coord = Cartesian2DCoordinate;
+    type Direction = CompassPrimary;
+    // Each side can have both an adjacent neighbour and a neighbour reached by tunnelling under it.
+    type CoordinateSmallVec = SmallVec<[Self::Coord; 8]>;
+    type CoordinateOptionSmallVec = SmallVec<[Option<Self::Coord>; 4]>;
+    type DirectionSmallVec = SmallVec<[CompassPrimary; 4]>;
+
+    fn offset_directions(coord: Option<Self::Coord>, dimensions: &dyn GridDimensions) -> Self::DirectionSmallVec {
+        SquareCell::offset_directions(coord, dimensions)
+    }
+
+    fn offset_coordinate(
+        coord: Self::Coord,
+        dir: Self::Direction,
+        dimensions: &dyn GridDimensions,
+    ) -> Option<Self::Coord> {
+        SquareCell::offset_coordinate(coord, dir, dimensions)
+    }
+
+    /// The adjacent cells, leaving out the sides of the cell taken by a tunnel beneath it, and the cells that can be,
+    /// or already are, reached by tunnelling under an adjacent cell.
+    fn neighbours(coord: Self::Coord, grid: &dyn GridLinks<Self>) -> Self::CoordinateSmallVec {
+        Self::offset_directions(Some(coord), grid.dimensions())
+            .iter()
+            .flat_map(|dir| Self::neighbours_at_direction(coord, *dir, grid))
+            .flatten()
+            .collect()
+    }
+
+    /// Any neighbour, including the cells beyond the adjacent ones that can be tunnelled to.
+    fn random_neighbour(coord: Self::Coord, grid: &dyn GridLinks<Self>, rng: &mut SmallRng) -> Option<Self::Coord> {
+        let neighbours = Self::neighbours(coord, grid);
+        if neighbours.is_empty() {
+            None
         } else {
-            ClockDirection::Outward(0)
+            Some(neighbours[rng.gen::<usize>() % neighbours.len()])
         }
     }
+
+    /// Any link but the corridor over a tunnel, which would leave the tunnel passing under a wall.
+    fn can_unlink(a: Self::Coord, b: Self::Coord, grid: &dyn GridLinks<Self>) -> bool {
+        // The corridor's passages can themselves be tunnels, to the cell beyond the adjacent one.
+        let is_tunnel_corridor = |over, other| {
+            Self::offset_directions(Some(over), grid.dimensions())
+                .iter()
+                .any(|dir| {
+                    let adjacent = grid.neighbour_at_direction(over, *dir);
+                    let beyond = adjacent.and_then(|adjacent_coord| grid.neighbour_at_direction(adjacent_coord, *dir));
+                    (adjacent == Some(other) || beyond == Some(other))
+                        && Self::is_tunnelled_under(grid, over, Self::crossing_directions(*dir)[0])
+                })
+        };
+        !is_tunnel_corridor(a, b) && !is_tunnel_corridor(b, a)
+    }
+
+    fn link_offsets() -> &'static [(u32, u32)] {
+        &[(1, 0), (0, 1), (2, 0), (0, 2)]
+    }
+
+    fn rand_direction(rng: &mut SmallRng, dimensions: &dyn GridDimensions, from: Self::Coord) -> Self::Direction {
+        SquareCell::rand_direction(rng, dimensions, from)
+    }
+
+    fn rand_roughly_vertical_direction(
+        rng: &mut SmallRng,
+        dimensions: &dyn GridDimensions,
+        from: Option<Self::Coord>,
+    ) -> Self::Direction {
+        SquareCell::rand_roughly_vertical_direction(rng, dimensions, from)
+    }
+
+    fn rand_roughly_horizontal_direction(
+        rng: &mut SmallRng,
+        dimensions: &dyn GridDimensions,
+        from: Option<Self::Coord>,
+    ) -> Self::Direction {
+        SquareCell::rand_roughly_horizontal_direction(rng, dimensions, from)
+    }
+}
+
+impl WeaveCell {
+    /// Is there a passage leaving the cell in the given direction, either linking to its neighbour or tunnelling
+    /// under the neighbour to the cell beyond it.
+    pub fn is_passage_towards(
+        grid: &dyn GridLinks<Self>,
+        coord: Cartesian2DCoordinate,
+        direction: CompassPrimary,
+    ) -> bool {
+        grid.neighbour_at_direction(coord, direction)
+            .is_some_and(|neighbour_coord| {
+                grid.is_linked(coord, neighbour_coord)
+                    || grid
+                        .neighbour_at_direction(neighbour_coord, direction)
+                        .is_some_and(|beyond_coord| grid.is_linked(coord, beyond_coord))
+            })
+    }
+
+    /// Does a tunnel pass beneath the cell in line with the given direction, linking the neighbours either side of
+    /// it.
+    pub fn is_tunnelled_under(
+        grid: &dyn GridLinks<Self>,
+        coord: Cartesian2DCoordinate,
+        direction: CompassPrimary,
+    ) -> bool {
+        let [side_a, side_b] = Self::in_line_directions(direction);
+        match (
+            grid.neighbour_at_direction(coord, side_a),
+            grid.neighbour_at_direction(coord, side_b),
+        ) {
+            (Some(a), Some(b)) => grid.is_linked(a, b),
+            _ => false,
+        }
+    }
+
+    /// The two directions of a corridor that a passage heading in the given direction crosses at right angles, so
+    /// could tunnel under.
+    fn crossing_directions(direction: CompassPrimary) -> [CompassPrimary; 2] {
+        match direction {
+            CompassPrimary::North | CompassPrimary::South => [CompassPrimary::East, CompassPrimary::West],
+            CompassPrimary::East | CompassPrimary::West => [CompassPrimary::North, CompassPrimary::South],
+        }
+    }
+
+    /// The given direction and its opposite.
+    fn in_line_directions(direction: CompassPrimary) -> [CompassPrimary; 2] {
+        Self::crossing_directions(Self::crossing_directions(direction)[0])
+    }
+
+    /// The neighbours of the cell on one side of it: the adjacent cell and the cell beyond it, reached by tunnelling
+    /// under the adjacent cell.
+    fn neighbours_at_direction(
+        coord: Cartesian2DCoordinate,
+        direction: CompassPrimary,
+        grid: &dyn GridLinks<Self>,
+    ) -> [Option<Cartesian2DCoordinate>; 2] {
+        let adjacent_coord = match grid.neighbour_at_direction(coord, direction) {
+            Some(adjacent_coord) => adjacent_coord,
+            None => return [None, None],
+        };
+        // A tunnel beneath this cell takes up this side of it.
+        if Self::is_tunnelled_under(grid, coord, direction) {
+            return [None, None];
+        }
+
+        let beyond_coord_opt = grid.neighbour_at_direction(adjacent_coord, direction);
+        if let Some(beyond_coord) = beyond_coord_opt {
+            if grid.is_linked(coord, beyond_coord) {
+                // Already tunnelled under, so the adjacent cell's side facing this cell is closed.
+                return [None, Some(beyond_coord)];
+            }
+        }
+
+        let can_tunnel_under_adjacent = Self::is_crossable_corridor(grid, adjacent_coord, direction);
+        [
+            Some(adjacent_coord),
+            beyond_coord_opt.filter(|_| can_tunnel_under_adjacent),
+        ]
+    }
+
+    /// Can a passage heading in the given direction tunnel under the cell? It must be a corridor open across the
+    /// passage, closed in line with it and not already have a tunnel beneath it.
+    fn is_crossable_corridor(
+        grid: &dyn GridLinks<Self>,
+        coord: Cartesian2DCoordinate,
+        direction: CompassPrimary,
+    ) -> bool {
+        let [crossing_a, crossing_b] = Self::crossing_directions(direction);
+        let [in_line_a, in_line_b] = Self::in_line_directions(direction);
+        Self::is_passage_towards(grid, coord, crossing_a)
+            && Self::is_passage_towards(grid, coord, crossing_b)
+            && !Self::is_passage_towards(grid, coord, in_line_a)
+            && !Self::is_passage_towards(grid, coord, in_line_b)
+            && !Self::is_tunnelled_under(grid, coord, in_line_a)
+            && !Self::is_tunnelled_under(grid, coord, crossing_a)
+    }
 }
 
 // Polar grid constructor
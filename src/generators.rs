@@ -1,17 +1,33 @@
+//! Maze generation algorithms.
+//!
+//! Every generator draws from the random number generator it is given, so the same seed regenerates the same maze
+//! on the same grid, e.g. with `SmallRng::seed_from_u64`.
+//!
+//! Any generator can also be run on an `ObservedGrid`, passing each step of how it carves the maze to an observer,
+//! e.g. to animate it, or through `GenerationSteps` to take the steps one at a time as they are asked for.
+
 use bit_set::BitSet;
 
 use crate::{
     cells::{Cartesian2DCoordinate, Cell, CompassPrimary, Coordinate, SquareCell},
-    grid::{Grid, IndexType},
+    grid::{CellLinkError, Grid, IndexType},
     grid_traits::GridIterators,
     masks::BinaryMask2D,
-    units::{ColumnLength, Height, RowLength, Width},
+    units::{ColumnsCount, Height, RowLength, RowsCount, Width},
     utils,
     utils::FnvHashSet,
 };
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use petgraph::unionfind::UnionFind;
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng};
 use smallvec::SmallVec;
-use std::cmp;
+use std::{
+    cmp::{self, Reverse},
+    collections::{BinaryHeap, VecDeque},
+    ops::{Deref, DerefMut},
+    panic,
+    sync::mpsc,
+    thread,
+};
 
 /// Apply the binary tree maze generation algorithm to a grid
 /// It works simply by visiting each cell in the grid and choosing to carve a passage
@@ -19,25 +35,28 @@ use std::cmp;
 /// Once picked, the two perpendicular directions are constant for the entire maze generation process,
 /// otherwise we'd have a good way for generating many areas with no way in or out. We would not be
 /// generating a perfect maze.
-pub fn binary_tree<GridIndexType, CellT, Iters>(grid: &mut Grid<GridIndexType, CellT, Iters>)
+pub fn binary_tree<GridIndexType, CellT, Iters, GridT>(mut grid: GridT, rng: &mut SmallRng)
 where
     GridIndexType: IndexType,
     CellT: Cell,
     Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
 {
-    let mut rng = SmallRng::from_entropy();
-    let neighbours_to_check = [
-        CellT::rand_roughly_vertical_direction(&mut rng, grid.dimensions(), None),
-        CellT::rand_roughly_horizontal_direction(&mut rng, grid.dimensions(), None),
-    ];
+    let vertical = CellT::rand_roughly_vertical_direction(rng, grid.dimensions(), None);
+    let horizontal = CellT::rand_roughly_horizontal_direction(rng, grid.dimensions(), None);
 
     for cell_coord in grid.iter() {
+        let neighbours_to_check = CellT::binary_tree_directions(cell_coord, vertical, horizontal, grid.dimensions());
         // Get the neighbours perpendicular to this cell
+        // A grid that wraps around, like a polar grid ring, is treated as if it had a boundary at the wrap point,
+        // otherwise a whole ring of cells could link to one another in a closed loop.
         let coord_opts: CellT::CoordinateOptionSmallVec =
             grid.neighbours_at_directions(cell_coord, &neighbours_to_check);
-        let neighbours = coord_opts
+        let neighbours = neighbours_to_check
             .iter()
-            .filter_map(|coord_maybe: &Option<CellT::Coord>| *coord_maybe)
+            .zip(coord_opts.iter())
+            .filter(|(dir, _)| !CellT::is_wrapping_offset(cell_coord, **dir, grid.dimensions()))
+            .filter_map(|(_, coord_maybe): (_, &Option<CellT::Coord>)| *coord_maybe)
             .collect::<CellT::CoordinateSmallVec>();
 
         // Unless there are no neighbours, randomly choose a neighbour to connect.
@@ -70,24 +89,23 @@ where
 /// if run direction does not match the order the direction/order we visit the cells in.
 /// So, if we visit the cells west to east, then the wall carving run direction needs to be east.
 /// The run closing out passage carving direction does not matter.
-pub fn sidewinder<GridIndexType, Iters>(grid: &mut Grid<GridIndexType, SquareCell, Iters>)
+pub fn sidewinder<GridIndexType, Iters, GridT>(mut grid: GridT, rng: &mut SmallRng)
 where
     GridIndexType: IndexType,
     Iters: GridIterators<SquareCell>,
+    GridT: CarveGrid<GridIndexType, SquareCell, Iters>,
 {
-    let mut rng = SmallRng::from_entropy();
-
     let runs_are_horizontal = rng.gen();
     let (next_in_run_direction, run_close_out_direction, batch_iter) = if runs_are_horizontal {
         (
             CompassPrimary::East,
-            SquareCell::rand_roughly_vertical_direction(&mut rng, grid.dimensions(), None),
+            SquareCell::rand_roughly_vertical_direction(rng, grid.dimensions(), None),
             grid.iter_row(),
         )
     } else {
         (
             CompassPrimary::South,
-            SquareCell::rand_roughly_horizontal_direction(&mut rng, grid.dimensions(), None),
+            SquareCell::rand_roughly_horizontal_direction(rng, grid.dimensions(), None),
             grid.iter_column(),
         )
     };
@@ -123,6 +141,190 @@ where
     }
 }
 
+/// Eller's algorithm, for rectangular grids.
+/// Works through the grid a row at a time, only keeping a record of which cells of the current row are already
+/// connected to each other (their set), so the generator state does not grow with the number of rows.
+/// Within a row, neighbouring cells from different sets are randomly linked and their sets merged. Then every set
+/// links at least one of its cells south, carrying the set into the next row. The last row links all of its
+/// remaining sets together.
+pub fn ellers<GridIndexType, Iters, GridT>(mut grid: GridT, rng: &mut SmallRng)
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+    GridT: CarveGrid<GridIndexType, SquareCell, Iters>,
+{
+    // The sets of the cells in the current row that were linked from the north.
+    let mut carried_sets: Vec<Option<usize>> = vec![];
+    let mut next_set_id = 0;
+
+    let mut rows = grid.iter_row().peekable();
+    while let Some(row) = rows.next() {
+        let is_last_row = rows.peek().is_none();
+
+        carried_sets.resize(row.len(), None);
+        let mut sets: Vec<usize> = carried_sets
+            .iter()
+            .map(|carried| {
+                carried.unwrap_or_else(|| {
+                    next_set_id += 1;
+                    next_set_id
+                })
+            })
+            .collect();
+
+        for x in 1..row.len() {
+            let (west_set, set) = (sets[x - 1], sets[x]);
+            if west_set != set && (is_last_row || rng.gen()) {
+                grid.link(row[x - 1], row[x])
+                    .expect("Failed to link a cell to its western neighbour.");
+                for s in sets.iter_mut().filter(|s| **s == set) {
+                    *s = west_set;
+                }
+            }
+        }
+
+        if !is_last_row {
+            // Visit the columns in a random order so that the one guaranteed southern link of a set is any of its cells.
+            let mut columns: Vec<usize> = (0..row.len()).collect();
+            columns.shuffle(rng);
+            let mut sets_linked_south: FnvHashSet<usize> = utils::fnv_hashset(row.len());
+            let mut next_carried_sets = vec![None; row.len()];
+
+            for x in columns {
+                let set = sets[x];
+                if !sets_linked_south.contains(&set) || rng.gen() {
+                    let south = grid
+                        .neighbour_at_direction(row[x], CompassPrimary::South)
+                        .expect("Every cell above the last row has a southern neighbour.");
+                    grid.link(row[x], south)
+                        .expect("Failed to link a cell to its southern neighbour.");
+                    sets_linked_south.insert(set);
+                    next_carried_sets[x] = Some(set);
+                }
+            }
+            carried_sets = next_carried_sets;
+        }
+    }
+}
+
+/// Recursive division, for rectangular grids.
+/// Unlike the passage carving generators this adds walls: every cell starts linked to all of its neighbours, see
+/// `Grid::link_all_neighbours`, then each region is bisected by a wall with a single gap in it, recursing into the two
+/// halves until the regions are one cell wide or high.
+/// When the grid index type cannot count that many links, e.g. on a large `small_rect_grid`, the same maze is carved
+/// instead, only linking the gaps in the walls and the regions left undivided.
+///
+/// Regions no more than `room_size` cells wide and high are left undivided as open rooms one time in four, as long
+/// as the grid index type can count the extra links a room has over a perfect maze.
+/// A `room_size` of 1 or less never leaves rooms, generating a perfect maze.
+pub fn recursive_division<GridIndexType, Iters, GridT>(mut grid: GridT, room_size: usize, rng: &mut SmallRng)
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+    GridT: CarveGrid<GridIndexType, SquareCell, Iters>,
+{
+    let ColumnsCount(columns_count) = grid.columns();
+    let RowsCount(rows_count) = grid.rows();
+
+    let adds_walls = grid.link_all_neighbours().is_ok();
+    let mut spare_links = if adds_walls {
+        // Report the links so that observers see the grid opened up before the walls are added.
+        let links: Vec<_> = grid.iter_links().collect();
+        for (a, b) in links {
+            grid.observe(GenerationEvent::Link(a, b));
+        }
+        usize::MAX
+    } else {
+        // Only the gaps and the undivided regions are linked. A perfect maze always fits, a room adds links to form
+        // loops.
+        grid.links_capacity().saturating_sub(grid.size().saturating_sub(1))
+    };
+
+    // Regions (x, y, width, height) still to divide, avoiding deep recursion on large grids.
+    let mut regions = vec![(0, 0, columns_count, rows_count)];
+    while let Some((x, y, width, height)) = regions.pop() {
+        let room_extra_links = width.saturating_sub(1) * height.saturating_sub(1);
+        let is_room =
+            width <= room_size && height <= room_size && room_extra_links <= spare_links && rng.gen_range(0..4) == 0;
+        if width <= 1 || height <= 1 || is_room {
+            if is_room {
+                spare_links -= room_extra_links;
+            }
+            if !adds_walls {
+                link_region(&mut grid, x, y, width, height);
+            }
+            continue;
+        }
+
+        let divide_horizontally = height > width || (height == width && rng.gen());
+        if divide_horizontally {
+            // The wall runs along the south side of row `y + north_height - 1`.
+            let north_height = rng.gen_range(1..height);
+            let gap = rng.gen_range(x..x + width);
+            for column in x..x + width {
+                let coord = Cartesian2DCoordinate::new(column as u32, (y + north_height - 1) as u32);
+                let south = Cartesian2DCoordinate::new(column as u32, (y + north_height) as u32);
+                build_wall(&mut grid, coord, south, column == gap, adds_walls);
+            }
+            regions.push((x, y, width, north_height));
+            regions.push((x, y + north_height, width, height - north_height));
+        } else {
+            // The wall runs along the east side of column `x + west_width - 1`.
+            let west_width = rng.gen_range(1..width);
+            let gap = rng.gen_range(y..y + height);
+            for row in y..y + height {
+                let coord = Cartesian2DCoordinate::new((x + west_width - 1) as u32, row as u32);
+                let east = Cartesian2DCoordinate::new((x + west_width) as u32, row as u32);
+                build_wall(&mut grid, coord, east, row == gap, adds_walls);
+            }
+            regions.push((x, y, west_width, height));
+            regions.push((x + west_width, y, width - west_width, height));
+        }
+    }
+}
+
+/// Build the part of a recursive division wall between two cells, unlinking them unless they are the gap in the wall.
+/// When carving the maze instead, only the gap is linked.
+fn build_wall<GridIndexType, Iters, GridT>(
+    grid: &mut GridT,
+    a: Cartesian2DCoordinate,
+    b: Cartesian2DCoordinate,
+    is_gap: bool,
+    adds_walls: bool,
+) where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+    GridT: CarveGrid<GridIndexType, SquareCell, Iters>,
+{
+    if adds_walls && !is_gap {
+        grid.unlink(a, b);
+    } else if !adds_walls && is_gap {
+        grid.link(a, b).expect("Failed to link the gap in a wall.");
+    }
+}
+
+/// Link every cell in the region (x, y, width, height) of a rectangular grid to its neighbours within the region.
+fn link_region<GridIndexType, Iters, GridT>(grid: &mut GridT, x: usize, y: usize, width: usize, height: usize)
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+    GridT: CarveGrid<GridIndexType, SquareCell, Iters>,
+{
+    for row in y..y + height {
+        for column in x..x + width {
+            let coord = Cartesian2DCoordinate::new(column as u32, row as u32);
+            if column + 1 < x + width {
+                grid.link(coord, Cartesian2DCoordinate::new(column as u32 + 1, row as u32))
+                    .expect("Failed to link cells within a region.");
+            }
+            if row + 1 < y + height {
+                grid.link(coord, Cartesian2DCoordinate::new(column as u32, row as u32 + 1))
+                    .expect("Failed to link cells within a region.");
+            }
+        }
+    }
+}
+
 /// Apply the Aldous-Broder random walk maze generation algorithm to the grid.
 /// Randomly walk from one cell to another until all have been visited. A new cell
 /// in the walk is linked to the previous one in the walks path whenever it is unvisited.
@@ -130,19 +332,20 @@ where
 ///
 /// Todo: handle masks that have walled off unreachable areas, making some unmasked cells unvisitable
 ///       and causing the algorithm to run forever.
-pub fn aldous_broder<GridIndexType, CellT, Iters>(
-    grid: &mut Grid<GridIndexType, CellT, Iters>,
+pub fn aldous_broder<GridIndexType, CellT, Iters, GridT>(
+    mut grid: GridT,
     mask: Option<&BinaryMask2D>,
+    rng: &mut SmallRng,
 ) where
     GridIndexType: IndexType,
     CellT: Cell,
     Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
 {
     let cells_count = grid.size();
-    let unmasked_count = unmasked_cells_count(grid, mask);
-    let mut rng = SmallRng::from_entropy();
+    let unmasked_count = unmasked_cells_count(&grid, mask);
 
-    let current_cell_opt = random_cell(grid, mask.map(|m| (m, unmasked_count)), &mut rng);
+    let current_cell_opt = random_cell(&grid, mask.map(|m| (m, unmasked_count)), rng);
     if current_cell_opt.is_none() {
         return;
     }
@@ -153,13 +356,13 @@ pub fn aldous_broder<GridIndexType, CellT, Iters>(
 
     let mut current_cell = current_cell_opt.unwrap();
 
-    visit_cell(current_cell, &mut visited_cells, Some(&mut visited_count), grid);
+    visit_cell(current_cell, &mut visited_cells, Some(&mut visited_count), &mut grid);
 
     while visited_count < unmasked_count {
         let next_cell = if let Some(m) = mask {
-            random_unmasked_neighbour(current_cell, grid, m, &mut rng)
+            random_unmasked_neighbour(current_cell, &grid, m, rng)
         } else {
-            random_neighbour(current_cell, grid, &mut rng)
+            random_neighbour(current_cell, &grid, rng)
         };
 
         // The random neighbour may not return a new cell that we can go to it, but it
@@ -167,11 +370,11 @@ pub fn aldous_broder<GridIndexType, CellT, Iters>(
         // random_unmasked_neighbour should achieve the same, even if the only unmasked neighbour
         // is backtracking to a previously visited cell
         if let Some(new_cell) = next_cell {
-            if !is_cell_in_visited_set(new_cell, &visited_cells, grid) {
+            if !is_cell_in_visited_set(new_cell, &visited_cells, &grid) {
                 grid.link(current_cell, new_cell)
                     .expect("Failed to link a cell on random walk.");
 
-                visit_cell(new_cell, &mut visited_cells, Some(&mut visited_count), grid);
+                visit_cell(new_cell, &mut visited_cells, Some(&mut visited_count), &mut grid);
             }
 
             current_cell = new_cell;
@@ -181,17 +384,17 @@ pub fn aldous_broder<GridIndexType, CellT, Iters>(
 
 /// Todo: handle masks that have walled off unreachable areas, making some unmasked cells unvisitable
 ///       and causing the algorithm to run forever.
-pub fn wilson<GridIndexType, CellT, Iters>(grid: &mut Grid<GridIndexType, CellT, Iters>, mask: Option<&BinaryMask2D>)
+pub fn wilson<GridIndexType, CellT, Iters, GridT>(mut grid: GridT, mask: Option<&BinaryMask2D>, rng: &mut SmallRng)
 where
     GridIndexType: IndexType,
     CellT: Cell,
     Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
 {
-    let unmasked_count = unmasked_cells_count(grid, mask);
+    let unmasked_count = unmasked_cells_count(&grid, mask);
     let mask_with_unmasked_count: Option<(&BinaryMask2D, usize)> = mask.map(|m| (m, unmasked_count));
-    let mut rng = SmallRng::from_entropy();
 
-    let start_cell = random_cell(grid, mask_with_unmasked_count, &mut rng);
+    let start_cell = random_cell(&grid, mask_with_unmasked_count, rng);
     if start_cell.is_none() {
         return;
     }
@@ -202,13 +405,18 @@ where
     let mut visited_count = 0;
 
     // Visit one cell randomly to start things off
-    visit_cell(start_cell.unwrap(), &mut visited_cells, Some(&mut visited_count), grid);
+    visit_cell(
+        start_cell.unwrap(),
+        &mut visited_cells,
+        Some(&mut visited_count),
+        &mut grid,
+    );
 
     // Need to keep the current walk's path, preferably with a quick way to check if a new cell forms a loop with the path.
     // The path is a sequence, i.e. Vec/Stack, but we want a quick way to look up if any particular coordinate is in that path.
-    let RowLength(row_len) = grid.row_length().expect("invalid row length");
-    let ColumnLength(col_len) = grid.column_length();
-    let mut cells_on_random_walk: FnvHashSet<CellT::Coord> = utils::fnv_hashset(cmp::max(row_len, col_len) * 4);
+    let ColumnsCount(columns) = grid.columns();
+    let RowsCount(rows) = grid.rows();
+    let mut cells_on_random_walk: FnvHashSet<CellT::Coord> = utils::fnv_hashset(cmp::max(columns, rows) * 4);
     let mut random_walk_path: Vec<CellT::Coord> = Vec::new();
 
     while visited_count < unmasked_count {
@@ -218,29 +426,45 @@ where
         random_walk_path.clear();
 
         let walk_start_cell = random_unvisited_unmasked_cell(
-            grid,
+            &grid,
             Some((&visited_cells, visited_count)),
             mask_with_unmasked_count,
-            &mut rng,
+            rng,
         )
         .expect("Error exhausted unmasked/unvisited cells");
         random_walk_path.push(walk_start_cell);
         cells_on_random_walk.insert(walk_start_cell);
+        grid.observe(GenerationEvent::Walk(walk_start_cell));
 
         'walking: loop {
             let current_walk_cell = *random_walk_path.last().unwrap();
 
-            if is_cell_in_visited_set(current_walk_cell, &visited_cells, grid) {
+            if is_cell_in_visited_set(current_walk_cell, &visited_cells, &grid) {
                 // We have a completed random walk path
-                // Link up the cells and visit them.
-                for (walk_index, cell) in random_walk_path.iter().enumerate() {
-                    visit_cell(*cell, &mut visited_cells, Some(&mut visited_count), grid);
-
-                    if walk_index > 0 {
-                        let path_previous_cell = random_walk_path[walk_index - 1];
-                        grid.link(*cell, path_previous_cell)
-                            .expect("Failed to link a cell on loop erased random walk path.");
+                // Link up the cells and visit them, back from the visited cell so that each one joins the maze.
+                // Linking can change the neighbours of cell types such as weave cells, e.g. the walk can no longer
+                // tunnel under the visited cell once it is linked in line with the tunnel, so the walk stops being
+                // linked at the first step that is no longer to a neighbour. Its remaining cells are left unvisited.
+                let mut unlinked_length = random_walk_path.len() - 1;
+                while unlinked_length > 0 {
+                    let cell = random_walk_path[unlinked_length];
+                    let path_previous_cell = random_walk_path[unlinked_length - 1];
+                    if !grid.neighbours(path_previous_cell).contains(&cell) {
+                        break;
                     }
+                    grid.link(cell, path_previous_cell)
+                        .expect("Failed to link a cell on loop erased random walk path.");
+                    visit_cell(
+                        path_previous_cell,
+                        &mut visited_cells,
+                        Some(&mut visited_count),
+                        &mut grid,
+                    );
+                    unlinked_length -= 1;
+                }
+                if unlinked_length > 0 {
+                    let unlinked_cells = random_walk_path[..unlinked_length].to_vec();
+                    grid.observe(GenerationEvent::EraseLoop(unlinked_cells));
                 }
 
                 // Look to start a new walk if there are any unvisited cells
@@ -248,9 +472,9 @@ where
             } else {
                 // Still randomly walking...
                 let walk_next = if let Some(m) = mask {
-                    random_unmasked_neighbour(current_walk_cell, grid, m, &mut rng)
+                    random_unmasked_neighbour(current_walk_cell, &grid, m, rng)
                 } else {
-                    random_neighbour(current_walk_cell, grid, &mut rng)
+                    random_neighbour(current_walk_cell, &grid, rng)
                 };
 
                 if let Some(new_cell) = walk_next {
@@ -266,11 +490,13 @@ where
                         for cell in random_walk_path.iter().skip(altered_path_length) {
                             cells_on_random_walk.remove(cell);
                         }
-                        random_walk_path.truncate(altered_path_length);
+                        let erased_loop = random_walk_path.split_off(altered_path_length);
+                        grid.observe(GenerationEvent::EraseLoop(erased_loop));
                     } else {
                         // Extend the walk
                         random_walk_path.push(new_cell);
                         cells_on_random_walk.insert(new_cell);
+                        grid.observe(GenerationEvent::Walk(new_cell));
                     }
                 }
             }
@@ -282,19 +508,20 @@ where
 /// Memory efficient - little beyond the grid to maintain.
 /// Compute challenged - visits every cells 2+ times, once in the walk and again in hunt phase.
 /// Executing the hunt phase many times can visit a cell many times.
-pub fn hunt_and_kill<GridIndexType, CellT, Iters>(
-    grid: &mut Grid<GridIndexType, CellT, Iters>,
+pub fn hunt_and_kill<GridIndexType, CellT, Iters, GridT>(
+    mut grid: GridT,
     mask: Option<&BinaryMask2D>,
+    rng: &mut SmallRng,
 ) where
     GridIndexType: IndexType,
     CellT: Cell,
     Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
 {
-    let unmasked_count = unmasked_cells_count(grid, mask);
+    let unmasked_count = unmasked_cells_count(&grid, mask);
     let mask_with_unmasked_count: Option<(&BinaryMask2D, usize)> = mask.map(|m| (m, unmasked_count));
-    let mut rng = SmallRng::from_entropy();
 
-    let start_cell = random_cell(grid, mask_with_unmasked_count, &mut rng);
+    let start_cell = random_cell(&grid, mask_with_unmasked_count, rng);
     if start_cell.is_none() {
         return;
     }
@@ -342,24 +569,24 @@ pub fn hunt_and_kill<GridIndexType, CellT, Iters>(
             }
         };
 
-    visit_cell(current_cell, &mut visited_cells, Some(&mut visited_count), grid);
+    visit_cell(current_cell, &mut visited_cells, Some(&mut visited_count), &mut grid);
 
     while visited_count < unmasked_count {
         let next_cell = if let Some(m) = mask {
-            random_unmasked_neighbour(current_cell, grid, m, &mut rng)
+            random_unmasked_neighbour(current_cell, &grid, m, rng)
         } else {
-            random_neighbour(current_cell, grid, &mut rng)
+            random_neighbour(current_cell, &grid, rng)
         };
 
         if let Some(new_cell) = next_cell {
-            if !is_cell_in_visited_set(new_cell, &visited_cells, grid) {
+            if !is_cell_in_visited_set(new_cell, &visited_cells, &grid) {
                 grid.link(current_cell, new_cell)
                     .expect("Failed to link a cell on random walk.");
 
-                visit_cell(new_cell, &mut visited_cells, Some(&mut visited_count), grid);
+                visit_cell(new_cell, &mut visited_cells, Some(&mut visited_count), &mut grid);
 
                 current_cell = new_cell;
-            } else if are_all_neighbours_visited_or_masked(current_cell, &visited_cells, grid, mask) {
+            } else if are_all_neighbours_visited_or_masked(current_cell, &visited_cells, &grid, mask) {
                 // The new_cell has been seen before, we are not allowed to go here...
                 // We will just try another random neighbour unless there are no unvisited neighbours
                 // in which case we take special steps to find one
@@ -369,15 +596,15 @@ pub fn hunt_and_kill<GridIndexType, CellT, Iters>(
                 let (hunted_cell, hunteds_visited_neighbours): (CellT::Coord, CellT::CoordinateSmallVec) = grid
                     .iter()
                     .skip_while(|cell| {
-                        is_cell_in_visited_set(*cell, &visited_cells, grid)
-                            || mask.map_or(false, |m| m.is_masked(*cell))
-                            || !is_any_neighbour_visited(*cell, &visited_cells, grid)
+                        is_cell_in_visited_set(*cell, &visited_cells, &grid)
+                            || mask.is_some_and(|m| m.is_masked(*cell))
+                            || !is_any_neighbour_visited(*cell, &visited_cells, &grid)
                     })
                     .take(1)
                     .fold(None, |_, cell| {
                         Some((
                             cell,
-                            visited_neighbours(cell, &visited_cells, grid)
+                            visited_neighbours(cell, &visited_cells, &grid)
                                 .expect("This cell should have 1+ visited neighbours"),
                         ))
                     })
@@ -389,7 +616,7 @@ pub fn hunt_and_kill<GridIndexType, CellT, Iters>(
                     hunteds_visited_neighbours[rng.gen::<usize>() % hunteds_visited_neighbours.len()];
                 grid.link(hunted_cell, random_visited_neighbour)
                     .expect("Failed to link the hunted cell to a random visited neighbour.");
-                visit_cell(hunted_cell, &mut visited_cells, Some(&mut visited_count), grid);
+                visit_cell(hunted_cell, &mut visited_cells, Some(&mut visited_count), &mut grid);
                 current_cell = hunted_cell;
             }
         }
@@ -400,19 +627,20 @@ pub fn hunt_and_kill<GridIndexType, CellT, Iters>(
 /// Generates a maze with lots of "river"/meandering - that is long runs before you encounter a dead end.
 /// Compute efficient - visits each cell exactly twice
 /// Memory challenged - the search stack can get very deep, up to grid size deep.
-pub fn recursive_backtracker<GridIndexType, CellT, Iters>(
-    grid: &mut Grid<GridIndexType, CellT, Iters>,
+pub fn recursive_backtracker<GridIndexType, CellT, Iters, GridT>(
+    mut grid: GridT,
     mask: Option<&BinaryMask2D>,
+    rng: &mut SmallRng,
 ) where
     GridIndexType: IndexType,
     CellT: Cell,
     Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
 {
-    let mut rng = SmallRng::from_entropy();
     let cells_count = grid.size();
-    let unmasked_count = unmasked_cells_count(grid, mask);
+    let unmasked_count = unmasked_cells_count(&grid, mask);
 
-    let start_cell_opt = random_cell(grid, mask.map(|m| (m, unmasked_count)), &mut rng);
+    let start_cell_opt = random_cell(&grid, mask.map(|m| (m, unmasked_count)), rng);
     if start_cell_opt.is_none() {
         return;
     }
@@ -449,9 +677,9 @@ pub fn recursive_backtracker<GridIndexType, CellT, Iters>(
 
     while !dfs_stack.is_empty() {
         let cell = *dfs_stack.last().expect("dfs stack should not be empty");
-        visit_cell(cell, &mut visited_cells, None, grid);
+        visit_cell(cell, &mut visited_cells, None, &mut grid);
 
-        let unvisited_neighbours_opt = unvisited_neighbours(cell, &visited_cells, grid);
+        let unvisited_neighbours_opt = unvisited_neighbours(cell, &visited_cells, &grid);
 
         if let Some(unvisited) = unvisited_neighbours_opt {
             let unvisited_count = unvisited.len();
@@ -465,21 +693,245 @@ pub fn recursive_backtracker<GridIndexType, CellT, Iters>(
             dfs_stack.push(next_cell);
         } else {
             dfs_stack.pop();
+            grid.observe(GenerationEvent::Backtrack(cell));
+        }
+    }
+}
+
+/// Simplified Prim's algorithm.
+/// Grows the maze outward from a random start cell, each step linking a random cell of the grown region (that still
+/// has unvisited neighbours) to one of its random unvisited neighbours.
+/// Generates a maze with a radial texture and lots of short dead ends.
+pub fn simplified_prims<GridIndexType, CellT, Iters, GridT>(
+    mut grid: GridT,
+    mask: Option<&BinaryMask2D>,
+    rng: &mut SmallRng,
+) where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
+{
+    let unmasked_count = unmasked_cells_count(&grid, mask);
+    let start_cell = if let Some(cell) = random_cell(&grid, mask.map(|m| (m, unmasked_count)), rng) {
+        cell
+    } else {
+        return;
+    };
+
+    let mut visited_cells = BitSet::with_capacity(grid.size());
+    visit_cell(start_cell, &mut visited_cells, None, &mut grid);
+    let mut active_cells = vec![start_cell];
+
+    while !active_cells.is_empty() {
+        let active_index = rng.gen::<usize>() % active_cells.len();
+        let cell = active_cells[active_index];
+
+        let unvisited = unvisited_unmasked_neighbours(cell, &visited_cells, mask, &grid);
+        if unvisited.is_empty() {
+            active_cells.swap_remove(active_index);
+            grid.observe(GenerationEvent::Backtrack(cell));
+        } else {
+            let next_cell = unvisited[rng.gen::<usize>() % unvisited.len()];
+            grid.link(cell, next_cell)
+                .expect("Failed to link an active cell to its unvisited neighbour.");
+            visit_cell(next_cell, &mut visited_cells, None, &mut grid);
+            active_cells.push(next_cell);
+        }
+    }
+}
+
+/// True Prim's algorithm.
+/// Every cell is given a random cost up front, then the maze grows outward from a random start cell by always
+/// linking the cheapest cell of the grown region (that still has unvisited neighbours) to its cheapest unvisited
+/// neighbour, i.e. building a minimum spanning tree.
+/// The radial texture is more pronounced than with `simplified_prims`.
+pub fn true_prims<GridIndexType, CellT, Iters, GridT>(mut grid: GridT, mask: Option<&BinaryMask2D>, rng: &mut SmallRng)
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
+{
+    const MAX_CELL_COST: u32 = 100;
+
+    let unmasked_count = unmasked_cells_count(&grid, mask);
+    let start_cell = if let Some(cell) = random_cell(&grid, mask.map(|m| (m, unmasked_count)), rng) {
+        cell
+    } else {
+        return;
+    };
+
+    let cell_costs: Vec<u32> = (0..grid.size()).map(|_| rng.gen_range(0..MAX_CELL_COST)).collect();
+    let cost = |cell: CellT::Coord, grid: &Grid<GridIndexType, CellT, Iters>| cell_costs[bit_index(cell, grid)];
+
+    let mut visited_cells = BitSet::with_capacity(grid.size());
+    visit_cell(start_cell, &mut visited_cells, None, &mut grid);
+    // Min heap of the active cells, ties broken by the coordinate ordering.
+    let mut active_cells = BinaryHeap::new();
+    active_cells.push(Reverse((cost(start_cell, &grid), start_cell)));
+
+    while let Some(&Reverse((_, cell))) = active_cells.peek() {
+        let unvisited = unvisited_unmasked_neighbours(cell, &visited_cells, mask, &grid);
+        if let Some(&next_cell) = unvisited.iter().min_by_key(|c| cost(**c, &grid)) {
+            grid.link(cell, next_cell)
+                .expect("Failed to link an active cell to its cheapest unvisited neighbour.");
+            visit_cell(next_cell, &mut visited_cells, None, &mut grid);
+            active_cells.push(Reverse((cost(next_cell, &grid), next_cell)));
+        } else {
+            active_cells.pop();
+            grid.observe(GenerationEvent::Backtrack(cell));
+        }
+    }
+}
+
+/// Randomised Kruskal's algorithm.
+/// Every wall between two neighbouring cells is considered once in a random order, and knocked down only if the
+/// cells either side of it are not yet connected, which is tracked with a disjoint-set (union-find).
+///
+/// Links already on the grid are kept and count as connections, so callers can force passages such as an entrance
+/// corridor before the algorithm fills in the rest. Pre-seeded links that form a loop leave the loop in the maze.
+pub fn kruskal<GridIndexType, CellT, Iters, GridT>(mut grid: GridT, mask: Option<&BinaryMask2D>, rng: &mut SmallRng)
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
+{
+    let mut connected_sets = UnionFind::<usize>::new(grid.size());
+    for (a, b) in grid.iter_links() {
+        connected_sets.union(bit_index(a, &grid), bit_index(b, &grid));
+    }
+
+    let is_unmasked = |c: CellT::Coord| !mask.is_some_and(|m| m.is_masked(c));
+    let mut walls: Vec<(CellT::Coord, CellT::Coord)> = Vec::with_capacity(grid.size() * 2);
+    for cell in grid.iter().filter(|c| is_unmasked(*c)) {
+        // Only take each wall once, from the cell with the lower index.
+        let cell_index = bit_index(cell, &grid);
+        for neighbour in grid.neighbours(cell).iter() {
+            if is_unmasked(*neighbour) && cell_index < bit_index(*neighbour, &grid) {
+                walls.push((cell, *neighbour));
+            }
+        }
+    }
+    walls.shuffle(rng);
+
+    for (a, b) in walls {
+        if connected_sets.union(bit_index(a, &grid), bit_index(b, &grid)) {
+            grid.link(a, b).expect("Failed to link cells from different sets.");
         }
     }
 }
 
-pub fn rebuild_random_walls<GridIndexType, CellT, Iters>(
-    grid: &mut Grid<GridIndexType, CellT, Iters>,
-    wall_count: usize,
+/// How the growing tree algorithm picks the next active cell to grow the maze from.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GrowingTreeSelection {
+    /// The most recently added cell, giving long winding passages like `recursive_backtracker`.
+    Newest,
+    /// Any active cell, giving the radial texture of `simplified_prims`.
+    Random,
+    /// The oldest active cell, giving long straight passages out from the start cell.
+    Oldest,
+    /// The newest cell with the given probability (0.0 to 1.0), otherwise a random cell.
+    /// Tunes the texture continuously between `Random` (0.0) and `Newest` (1.0). Probabilities outside that range are
+    /// clamped to it, and NaN is treated as 0.0.
+    NewestOrRandom(f64),
+}
+
+impl GrowingTreeSelection {
+    fn choose_index(self, active_count: usize, rng: &mut SmallRng) -> usize {
+        match self {
+            GrowingTreeSelection::Newest => active_count - 1,
+            GrowingTreeSelection::Random => rng.gen::<usize>() % active_count,
+            GrowingTreeSelection::Oldest => 0,
+            GrowingTreeSelection::NewestOrRandom(newest_probability) => {
+                let newest_probability = if newest_probability.is_nan() {
+                    0.0
+                } else {
+                    newest_probability.clamp(0.0, 1.0)
+                };
+                if rng.gen_bool(newest_probability) {
+                    active_count - 1
+                } else {
+                    rng.gen::<usize>() % active_count
+                }
+            }
+        }
+    }
+}
+
+/// Growing tree algorithm.
+/// Grows the maze outward from a random start cell, each step linking an active cell to a random unvisited
+/// neighbour, which becomes active itself. Active cells with no unvisited neighbours are retired.
+/// The `selection` strategy for which active cell to grow from determines the texture of the maze.
+pub fn growing_tree<GridIndexType, CellT, Iters, GridT>(
+    mut grid: GridT,
+    mask: Option<&BinaryMask2D>,
+    selection: GrowingTreeSelection,
+    rng: &mut SmallRng,
 ) where
     GridIndexType: IndexType,
     CellT: Cell,
     Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
+{
+    let unmasked_count = unmasked_cells_count(&grid, mask);
+    let start_cell = if let Some(cell) = random_cell(&grid, mask.map(|m| (m, unmasked_count)), rng) {
+        cell
+    } else {
+        return;
+    };
+
+    let mut visited_cells = BitSet::with_capacity(grid.size());
+    visit_cell(start_cell, &mut visited_cells, None, &mut grid);
+    // Oldest first, newest last.
+    let mut active_cells = VecDeque::from(vec![start_cell]);
+
+    while !active_cells.is_empty() {
+        let active_index = selection.choose_index(active_cells.len(), rng);
+        let cell = active_cells[active_index];
+
+        let unvisited = unvisited_unmasked_neighbours(cell, &visited_cells, mask, &grid);
+        if unvisited.is_empty() {
+            // The selections rely on the newest cell staying at the back and the oldest at the front, so retiring a
+            // cell from the middle moves the front cell into its place. Only `Oldest` cares where the oldest cell is,
+            // and it only ever retires the front cell.
+            if active_index + 1 == active_cells.len() {
+                active_cells.pop_back();
+            } else {
+                active_cells.swap_remove_front(active_index);
+            }
+            grid.observe(GenerationEvent::Backtrack(cell));
+        } else {
+            let next_cell = unvisited[rng.gen::<usize>() % unvisited.len()];
+            grid.link(cell, next_cell)
+                .expect("Failed to link an active cell to its unvisited neighbour.");
+            visit_cell(next_cell, &mut visited_cells, None, &mut grid);
+            active_cells.push_back(next_cell);
+        }
+    }
+}
+
+pub fn rebuild_random_walls<GridIndexType, CellT, Iters, GridT>(mut grid: GridT, wall_count: usize, rng: &mut SmallRng)
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
 {
+    // Some cell types need links kept, e.g. the corridor over a weave cell's tunnel.
+    let removable_links = |coord, grid: &Grid<GridIndexType, CellT, Iters>| -> CellT::CoordinateSmallVec {
+        grid.links(coord)
+            .expect("Should always have a valid cell coordinate")
+            .iter()
+            .cloned()
+            .filter(|linked| CellT::can_unlink(coord, *linked, grid))
+            .collect()
+    };
+
     let max_rebuildable_cells = grid
         .iter()
-        .filter(|coord| grid.links(*coord).unwrap().len() > 0)
+        .filter(|coord| !removable_links(*coord, &grid).is_empty())
         .count();
     let build_target_count = if wall_count < max_rebuildable_cells {
         wall_count
@@ -487,16 +939,12 @@ pub fn rebuild_random_walls<GridIndexType, CellT, Iters>(
         max_rebuildable_cells
     };
 
-    let mut rng = SmallRng::from_entropy();
     let mut cells_with_wall_rebuilt: FnvHashSet<CellT::Coord> = utils::fnv_hashset(build_target_count);
 
     while cells_with_wall_rebuilt.len() < build_target_count {
-        let cell_coord =
-            random_cell(grid, None, &mut rng).expect("Should always get a random cell if not using a Mask");
+        let cell_coord = random_cell(&grid, None, rng).expect("Should always get a random cell if not using a Mask");
         if !cells_with_wall_rebuilt.contains(&cell_coord) {
-            let adjacent_linked_cells = grid
-                .links(cell_coord)
-                .expect("Should always have a valid random cell coordinate");
+            let adjacent_linked_cells = removable_links(cell_coord, &grid);
             let adjacents_count = adjacent_linked_cells.len();
             if adjacents_count > 0 {
                 let linked: CellT::Coord = adjacent_linked_cells[rng.gen::<usize>() % adjacents_count];
@@ -509,6 +957,267 @@ pub fn rebuild_random_walls<GridIndexType, CellT, Iters>(
     }
 }
 
+/// Braid a maze, removing dead ends to create loops so that there are multiple routes between cells.
+/// Each dead end (a cell with exactly one link) is, with probability `p`, linked to one of its unlinked neighbours,
+/// preferring a neighbour that is also a dead end so that one new link can remove two dead ends.
+/// Cells with no links, such as masked cells, are never linked to.
+/// Only neighbours as given by `Grid::neighbours` are linked to, so on weave grids no link is made across a tunnel.
+/// A `p` of 0.0 leaves the maze unchanged and 1.0 removes every dead end, unless the grid runs out of links first:
+/// braiding stops once the grid holds as many links as its index type can count, see `Grid::links_capacity`.
+///
+/// Panics if `p` is not in the range 0.0 to 1.0.
+pub fn braid<GridIndexType, CellT, Iters, GridT>(mut grid: GridT, p: f64, rng: &mut SmallRng)
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
+{
+    let links_count =
+        |grid: &Grid<GridIndexType, CellT, Iters>, coord| grid.links(coord).map_or(0, |links| links.len());
+
+    let mut dead_ends: Vec<CellT::Coord> = grid.iter().filter(|coord| links_count(&grid, *coord) == 1).collect();
+    dead_ends.shuffle(rng);
+
+    for cell in dead_ends {
+        if grid.links_count() >= grid.links_capacity() {
+            break;
+        }
+
+        // An earlier braid link may have already removed this dead end.
+        if links_count(&grid, cell) != 1 || !rng.gen_bool(p) {
+            continue;
+        }
+
+        let unlinked_neighbours: Vec<CellT::Coord> = grid
+            .neighbours(cell)
+            .iter()
+            .cloned()
+            .filter(|neighbour| !grid.is_linked(cell, *neighbour) && links_count(&grid, *neighbour) > 0)
+            .collect();
+        let dead_end_neighbours: Vec<CellT::Coord> = unlinked_neighbours
+            .iter()
+            .cloned()
+            .filter(|neighbour| links_count(&grid, *neighbour) == 1)
+            .collect();
+
+        let candidates = if dead_end_neighbours.is_empty() {
+            &unlinked_neighbours
+        } else {
+            &dead_end_neighbours
+        };
+        if let Some(neighbour) = candidates.choose(rng) {
+            grid.link(cell, *neighbour)
+                .expect("Failed to link a dead end to its neighbour.");
+        }
+    }
+}
+
+/// A step taken by a maze generator, see `ObservedGrid`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GenerationEvent<Coord> {
+    /// A cell joined the maze.
+    Visit(Coord),
+    /// A passage was carved between two cells.
+    Link(Coord, Coord),
+    /// A wall was built between two cells.
+    Unlink(Coord, Coord),
+    /// A cell has no unvisited neighbours left so the generator is done with it, e.g. popping it off the recursive
+    /// backtracker's stack.
+    Backtrack(Coord),
+    /// A random walk moved to a cell without joining it to the maze yet, as in Wilson's algorithm.
+    Walk(Coord),
+    /// Cells are erased from a random walk without joining the maze, e.g. the cells walked since the walk was last at
+    /// a cell it has crossed back over.
+    EraseLoop(Vec<Coord>),
+}
+
+impl<Coord: Coordinate> GenerationEvent<Coord> {
+    /// Make the change to the grid that this event describes, if any, e.g. to replay generation on another grid.
+    pub fn apply<GridIndexType, CellT, Iters>(&self, grid: &mut Grid<GridIndexType, CellT, Iters>)
+    where
+        GridIndexType: IndexType,
+        CellT: Cell<Coord = Coord>,
+        Iters: GridIterators<CellT>,
+    {
+        match *self {
+            GenerationEvent::Link(a, b) => grid.link(a, b).expect("Failed to replay a link."),
+            GenerationEvent::Unlink(a, b) => {
+                let _ = grid.unlink(a, b);
+            }
+            GenerationEvent::Visit(_)
+            | GenerationEvent::Backtrack(_)
+            | GenerationEvent::Walk(_)
+            | GenerationEvent::EraseLoop(_) => {}
+        }
+    }
+}
+
+/// A grid that the generators carve a maze into, reporting each step of the generation as it is taken.
+///
+/// Implemented for `&mut Grid`, which ignores the steps, and for `ObservedGrid`, which passes them to an observer.
+pub trait CarveGrid<GridIndexType, CellT, Iters>: DerefMut<Target = Grid<GridIndexType, CellT, Iters>>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    /// Report a step of the generation.
+    fn observe(&mut self, event: GenerationEvent<CellT::Coord>);
+
+    /// Link two cells as `Grid::link` does, reporting the link.
+    fn link(&mut self, a: CellT::Coord, b: CellT::Coord) -> Result<(), CellLinkError> {
+        Grid::link(self, a, b)?;
+        self.observe(GenerationEvent::Link(a, b));
+        Ok(())
+    }
+
+    /// Unlink two cells as `Grid::unlink` does, reporting the unlink if there was a link.
+    fn unlink(&mut self, a: CellT::Coord, b: CellT::Coord) -> bool {
+        let is_unlinked = Grid::unlink(self, a, b);
+        if is_unlinked {
+            self.observe(GenerationEvent::Unlink(a, b));
+        }
+        is_unlinked
+    }
+}
+
+impl<GridIndexType, CellT, Iters> CarveGrid<GridIndexType, CellT, Iters> for &mut Grid<GridIndexType, CellT, Iters>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    #[inline]
+    fn observe(&mut self, _: GenerationEvent<CellT::Coord>) {}
+}
+
+/// A grid whose generation steps are passed to an observer as the generator takes them, e.g. to animate how the
+/// maze was carved:
+/// `recursive_backtracker(ObservedGrid::new(&mut grid, &mut |step| steps.push(step)), None, &mut rng)`.
+///
+/// Applying the steps in order to an empty grid of the same shape replays the generation.
+pub struct ObservedGrid<'a, GridIndexType, CellT, Iters>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    grid: &'a mut Grid<GridIndexType, CellT, Iters>,
+    observer: &'a mut dyn FnMut(GenerationEvent<CellT::Coord>),
+}
+
+impl<'a, GridIndexType, CellT, Iters> ObservedGrid<'a, GridIndexType, CellT, Iters>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    pub fn new(
+        grid: &'a mut Grid<GridIndexType, CellT, Iters>,
+        observer: &'a mut dyn FnMut(GenerationEvent<CellT::Coord>),
+    ) -> Self {
+        ObservedGrid { grid, observer }
+    }
+
+    /// Borrow the grid and observer again, so that more than one generator can run on them, e.g. generating a maze
+    /// and then braiding it.
+    pub fn reborrow(&mut self) -> ObservedGrid<'_, GridIndexType, CellT, Iters> {
+        ObservedGrid {
+            grid: self.grid,
+            observer: self.observer,
+        }
+    }
+}
+
+impl<GridIndexType, CellT, Iters> Deref for ObservedGrid<'_, GridIndexType, CellT, Iters>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    type Target = Grid<GridIndexType, CellT, Iters>;
+    fn deref(&self) -> &Self::Target {
+        self.grid
+    }
+}
+
+impl<GridIndexType, CellT, Iters> DerefMut for ObservedGrid<'_, GridIndexType, CellT, Iters>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.grid
+    }
+}
+
+impl<GridIndexType, CellT, Iters> CarveGrid<GridIndexType, CellT, Iters>
+    for ObservedGrid<'_, GridIndexType, CellT, Iters>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    fn observe(&mut self, event: GenerationEvent<CellT::Coord>) {
+        (self.observer)(event);
+    }
+}
+
+/// The steps of a maze generation, each taken only once the step before it has been asked for, e.g. to single-step
+/// an animation or a lesson on how the generator carves.
+///
+/// The generation runs on its own thread, waiting after each step. Grids cannot be moved between threads, so the
+/// generation makes its own grid and runs generators on an `ObservedGrid` of it with the observer it is given:
+/// `GenerationSteps::new(move |observer| recursive_backtracker(ObservedGrid::new(&mut grid(), observer), None, &mut
+/// SmallRng::seed_from_u64(seed)))`.
+///
+/// Applying the steps in order to an empty grid of the same shape shows the maze as it is carved, see
+/// `GenerationEvent::apply`. Dropping the steps early leaves the generation to finish on its own.
+pub struct GenerationSteps<Coord> {
+    steps: mpsc::Receiver<GenerationEvent<Coord>>,
+    generation: Option<thread::JoinHandle<()>>,
+}
+
+impl<Coord> GenerationSteps<Coord>
+where
+    Coord: Coordinate + Send + 'static,
+{
+    pub fn new<F>(generate: F) -> Self
+    where
+        F: FnOnce(&mut dyn FnMut(GenerationEvent<Coord>)) + Send + 'static,
+    {
+        // Without a buffer each step waits for the receiver to ask for it.
+        let (sender, steps) = mpsc::sync_channel(0);
+        let generation = thread::spawn(move || {
+            generate(&mut |step| {
+                // Nobody is asking for steps once they are dropped, so the generation finishes without them.
+                let _ = sender.send(step);
+            })
+        });
+        GenerationSteps {
+            steps,
+            generation: Some(generation),
+        }
+    }
+}
+
+impl<Coord> Iterator for GenerationSteps<Coord> {
+    type Item = GenerationEvent<Coord>;
+
+    /// Returns `None` once the generation finishes, panicking if the generation panicked.
+    fn next(&mut self) -> Option<Self::Item> {
+        let step = self.steps.recv().ok();
+        if step.is_none() {
+            if let Some(Err(generation_panic)) = self.generation.take().map(thread::JoinHandle::join) {
+                panic::resume_unwind(generation_panic);
+            }
+        }
+        step
+    }
+}
+
 #[inline]
 fn random_neighbour<GridIndexType, CellT, Iters>(
     cell: CellT::Coord,
@@ -520,7 +1229,7 @@ where
     CellT: Cell,
     Iters: GridIterators<CellT>,
 {
-    grid.neighbour_at_direction(cell, CellT::rand_direction(rng, grid.dimensions(), cell))
+    CellT::random_neighbour(cell, grid, rng)
 }
 
 fn random_cell<GridIndexType, CellT, Iters>(
@@ -565,18 +1274,22 @@ where
     visited_set.contains(bit_index(cell, grid))
 }
 
-fn visit_cell<GridIndexType, CellT, Iters>(
+fn visit_cell<GridIndexType, CellT, Iters, GridT>(
     cell: CellT::Coord,
     visited_set: &mut BitSet,
     visited_count: Option<&mut usize>,
-    grid: &Grid<GridIndexType, CellT, Iters>,
+    grid: &mut GridT,
 ) -> bool
 where
     GridIndexType: IndexType,
     CellT: Cell,
     Iters: GridIterators<CellT>,
+    GridT: CarveGrid<GridIndexType, CellT, Iters>,
 {
     let is_previously_unvisited = visited_set.insert(bit_index(cell, grid));
+    if is_previously_unvisited {
+        grid.observe(GenerationEvent::Visit(cell));
+    }
     if let Some(count) = visited_count {
         if is_previously_unvisited {
             *count += 1;
@@ -712,6 +1425,24 @@ where
     }
 }
 
+fn unvisited_unmasked_neighbours<GridIndexType, CellT, Iters>(
+    cell: CellT::Coord,
+    visited_set: &BitSet,
+    mask: Option<&BinaryMask2D>,
+    grid: &Grid<GridIndexType, CellT, Iters>,
+) -> CellT::CoordinateSmallVec
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    grid.neighbours(cell)
+        .iter()
+        .cloned()
+        .filter(|c| !is_cell_in_visited_set(*c, visited_set, grid) && !mask.is_some_and(|m| m.is_masked(*c)))
+        .collect()
+}
+
 fn unmasked_cells_count<GridIndexType, CellT, Iters>(
     grid: &Grid<GridIndexType, CellT, Iters>,
     mask: Option<&BinaryMask2D>,
@@ -722,11 +1453,639 @@ where
     Iters: GridIterators<CellT>,
 {
     if let Some(m) = mask {
-        m.count_unmasked_within_dimensions(
-            Width(grid.row_length().expect("invalid rowlength").0),
-            Height(grid.column_length().0),
-        )
+        if let Some(RowLength(width)) = grid.row_length() {
+            m.count_unmasked_within_dimensions(Width(width), Height(grid.column_length().0))
+        } else {
+            // Rows of varying length, e.g. a polar grid, do not form a rectangle.
+            grid.iter().filter(|coord| !m.is_masked(*coord)).count()
+        }
     } else {
         grid.size()
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::cells::WeaveCell;
+    use crate::grid_iterators::RectGridIterators;
+    use crate::grids::{hex_grid, polar_grid, triangle_grid, weave_grid, HexGrid, PolarGrid, TriangleGrid, WeaveGrid};
+    use crate::grids::{medium_rect_grid, small_rect_grid, SmallRectangularGrid};
+    use crate::pathing::Distances;
+    use crate::units::{ColumnLength, RowsCount};
+    use rand::SeedableRng;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    fn assert_perfect_maze<GridIndexType, CellT, Iters>(grid: &Grid<GridIndexType, CellT, Iters>)
+    where
+        GridIndexType: IndexType,
+        CellT: Cell,
+        Iters: GridIterators<CellT>,
+    {
+        // A perfect maze is a spanning tree, every cell reachable with exactly one route between any two cells.
+        assert_eq!(grid.links_count(), grid.size() - 1);
+        let start = grid.iter().next().unwrap();
+        let distances = Distances::<CellT, u32>::for_grid(grid, start).unwrap();
+        assert_eq!(distances.distances().len(), grid.size());
+    }
+
+    fn check_polar_generator(generator: fn(&mut PolarGrid, &mut SmallRng)) {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for rows in 1..12 {
+            let mut g = polar_grid(RowsCount(rows)).unwrap();
+            generator(&mut g, &mut rng);
+            assert_perfect_maze(&g);
+        }
+    }
+
+    fn check_hex_generator(generator: fn(&mut HexGrid, &mut SmallRng)) {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for (w, h) in [(1, 1), (1, 5), (5, 1), (2, 2), (7, 4), (12, 12)] {
+            let mut g = hex_grid(RowLength(w), ColumnLength(h)).unwrap();
+            generator(&mut g, &mut rng);
+            assert_perfect_maze(&g);
+        }
+    }
+
+    fn check_triangle_generator(generator: fn(&mut TriangleGrid, &mut SmallRng)) {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for (w, h) in [(2, 1), (5, 1), (2, 2), (2, 5), (7, 4), (12, 12)] {
+            let mut g = triangle_grid(RowLength(w), ColumnLength(h)).unwrap();
+            generator(&mut g, &mut rng);
+            assert_perfect_maze(&g);
+        }
+    }
+
+    #[test]
+    fn same_seed_generates_same_maze() {
+        let generators: [fn(&mut SmallRectangularGrid, &mut SmallRng); 12] = [
+            |g, rng| binary_tree(g, rng),
+            |g, rng| sidewinder(g, rng),
+            |g, rng| ellers(g, rng),
+            |g, rng| aldous_broder(g, None, rng),
+            |g, rng| wilson(g, None, rng),
+            |g, rng| hunt_and_kill(g, None, rng),
+            |g, rng| recursive_backtracker(g, None, rng),
+            |g, rng| simplified_prims(g, None, rng),
+            |g, rng| true_prims(g, None, rng),
+            |g, rng| kruskal(g, None, rng),
+            |g, rng| growing_tree(g, None, GrowingTreeSelection::NewestOrRandom(0.75), rng),
+            |g, rng| {
+                recursive_backtracker(&mut *g, None, rng);
+                rebuild_random_walls(g, 10, rng);
+            },
+        ];
+        for generator in generators.iter() {
+            let generate = |seed| {
+                let mut g = small_rect_grid(RowLength(12), ColumnLength(9)).unwrap();
+                generator(&mut g, &mut SmallRng::seed_from_u64(seed));
+                g.iter_links().collect::<Vec<_>>()
+            };
+            assert_eq!(generate(42), generate(42));
+            assert_ne!(generate(42), generate(43));
+        }
+    }
+
+    #[test]
+    fn binary_tree_polar() {
+        check_polar_generator(|g, rng| binary_tree(g, rng));
+    }
+
+    #[test]
+    fn aldous_broder_polar() {
+        check_polar_generator(|g, rng| aldous_broder(g, None, rng));
+    }
+
+    #[test]
+    fn wilson_polar() {
+        check_polar_generator(|g, rng| wilson(g, None, rng));
+    }
+
+    #[test]
+    fn hunt_and_kill_polar() {
+        check_polar_generator(|g, rng| hunt_and_kill(g, None, rng));
+    }
+
+    #[test]
+    fn recursive_backtracker_polar() {
+        check_polar_generator(|g, rng| recursive_backtracker(g, None, rng));
+    }
+
+    #[test]
+    fn binary_tree_hex() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for (w, h) in [(1, 2), (2, 2), (7, 4), (12, 12)] {
+            let mut g = hex_grid(RowLength(w), ColumnLength(h)).unwrap();
+            binary_tree(&mut g, &mut rng);
+            assert_perfect_maze(&g);
+        }
+    }
+
+    #[test]
+    fn aldous_broder_hex() {
+        check_hex_generator(|g, rng| aldous_broder(g, None, rng));
+    }
+
+    #[test]
+    fn wilson_hex() {
+        check_hex_generator(|g, rng| wilson(g, None, rng));
+    }
+
+    #[test]
+    fn hunt_and_kill_hex() {
+        check_hex_generator(|g, rng| hunt_and_kill(g, None, rng));
+    }
+
+    #[test]
+    fn recursive_backtracker_hex() {
+        check_hex_generator(|g, rng| recursive_backtracker(g, None, rng));
+    }
+
+    #[test]
+    fn binary_tree_triangle() {
+        check_triangle_generator(|g, rng| binary_tree(g, rng));
+        // Each seed picks its own pairing of vertical and horizontal directions.
+        for seed in 0..16 {
+            for (w, h) in [(6, 6), (7, 5), (2, 3)] {
+                let mut g = triangle_grid(RowLength(w), ColumnLength(h)).unwrap();
+                binary_tree(&mut g, &mut SmallRng::seed_from_u64(seed));
+                assert_perfect_maze(&g);
+            }
+        }
+    }
+
+    #[test]
+    fn aldous_broder_triangle() {
+        check_triangle_generator(|g, rng| aldous_broder(g, None, rng));
+    }
+
+    #[test]
+    fn wilson_triangle() {
+        check_triangle_generator(|g, rng| wilson(g, None, rng));
+    }
+
+    #[test]
+    fn hunt_and_kill_triangle() {
+        check_triangle_generator(|g, rng| hunt_and_kill(g, None, rng));
+    }
+
+    #[test]
+    fn recursive_backtracker_triangle() {
+        check_triangle_generator(|g, rng| recursive_backtracker(g, None, rng));
+    }
+
+    #[test]
+    fn simplified_prims_polar() {
+        check_polar_generator(|g, rng| simplified_prims(g, None, rng));
+    }
+
+    #[test]
+    fn true_prims_polar() {
+        check_polar_generator(|g, rng| true_prims(g, None, rng));
+    }
+
+    #[test]
+    fn simplified_prims_hex() {
+        check_hex_generator(|g, rng| simplified_prims(g, None, rng));
+    }
+
+    #[test]
+    fn true_prims_hex() {
+        check_hex_generator(|g, rng| true_prims(g, None, rng));
+    }
+
+    #[test]
+    fn simplified_prims_triangle() {
+        check_triangle_generator(|g, rng| simplified_prims(g, None, rng));
+    }
+
+    #[test]
+    fn true_prims_triangle() {
+        check_triangle_generator(|g, rng| true_prims(g, None, rng));
+    }
+
+    #[test]
+    fn masked_cells_are_left_unlinked() {
+        // Mask out the whole top row and one cell in the middle of a 5x5 grid.
+        let mut mask_image = image::GrayImage::from_pixel(5, 5, image::Luma([255]));
+        for x in 0..5 {
+            mask_image.put_pixel(x, 0, image::Luma([0]));
+        }
+        mask_image.put_pixel(2, 2, image::Luma([0]));
+        let mask = BinaryMask2D::from_image(&image::DynamicImage::ImageLuma8(mask_image));
+
+        let generators: [fn(&mut SmallRectangularGrid, Option<&BinaryMask2D>, &mut SmallRng); 4] = [
+            |g, mask, rng| simplified_prims(g, mask, rng),
+            |g, mask, rng| true_prims(g, mask, rng),
+            |g, mask, rng| kruskal(g, mask, rng),
+            |g, mask, rng| growing_tree(g, mask, GrowingTreeSelection::Oldest, rng),
+        ];
+        for generator in generators.iter() {
+            let mut g = small_rect_grid(RowLength(5), ColumnLength(5)).unwrap();
+            generator(&mut g, Some(&mask), &mut SmallRng::seed_from_u64(1));
+
+            let unmasked_count = 5 * 5 - 6;
+            assert_eq!(g.links_count(), unmasked_count - 1);
+            for cell in g.iter().filter(|c| mask.is_masked(*c)) {
+                assert!(g.links(cell).unwrap().is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn kruskal_polar() {
+        check_polar_generator(|g, rng| kruskal(g, None, rng));
+    }
+
+    #[test]
+    fn kruskal_hex() {
+        check_hex_generator(|g, rng| kruskal(g, None, rng));
+    }
+
+    #[test]
+    fn kruskal_triangle() {
+        check_triangle_generator(|g, rng| kruskal(g, None, rng));
+    }
+
+    #[test]
+    fn kruskal_keeps_pre_seeded_links() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let corridor = [gc(0, 3), gc(1, 3), gc(2, 3), gc(3, 3), gc(4, 3)];
+
+        for _ in 0..10 {
+            let mut g = small_rect_grid(RowLength(8), ColumnLength(6)).unwrap();
+            for pair in corridor.windows(2) {
+                g.link(pair[0], pair[1]).unwrap();
+            }
+            kruskal(&mut g, None, &mut rng);
+
+            assert_perfect_maze(&g);
+            assert!(corridor.windows(2).all(|pair| g.is_linked(pair[0], pair[1])));
+        }
+    }
+
+    const GROWING_TREE_SELECTIONS: [GrowingTreeSelection; 6] = [
+        GrowingTreeSelection::Newest,
+        GrowingTreeSelection::Random,
+        GrowingTreeSelection::Oldest,
+        GrowingTreeSelection::NewestOrRandom(0.0),
+        GrowingTreeSelection::NewestOrRandom(0.75),
+        GrowingTreeSelection::NewestOrRandom(1.0),
+    ];
+
+    #[test]
+    fn growing_tree_selections_generate_perfect_mazes() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for selection in GROWING_TREE_SELECTIONS.iter() {
+            let mut g = small_rect_grid(RowLength(9), ColumnLength(7)).unwrap();
+            growing_tree(&mut g, None, *selection, &mut rng);
+            assert_perfect_maze(&g);
+        }
+    }
+
+    #[test]
+    fn growing_tree_polar() {
+        check_polar_generator(|g, rng| growing_tree(g, None, GrowingTreeSelection::NewestOrRandom(0.5), rng));
+    }
+
+    #[test]
+    fn growing_tree_hex() {
+        check_hex_generator(|g, rng| growing_tree(g, None, GrowingTreeSelection::NewestOrRandom(0.5), rng));
+    }
+
+    #[test]
+    fn growing_tree_triangle() {
+        check_triangle_generator(|g, rng| growing_tree(g, None, GrowingTreeSelection::NewestOrRandom(0.5), rng));
+    }
+
+    #[test]
+    fn growing_tree_selection_indices() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        assert_eq!(GrowingTreeSelection::Newest.choose_index(5, &mut rng), 4);
+        assert_eq!(GrowingTreeSelection::Oldest.choose_index(5, &mut rng), 0);
+        assert_eq!(GrowingTreeSelection::NewestOrRandom(1.0).choose_index(5, &mut rng), 4);
+        for _ in 0..100 {
+            assert!(GrowingTreeSelection::Random.choose_index(5, &mut rng) < 5);
+            assert!(GrowingTreeSelection::NewestOrRandom(0.5).choose_index(5, &mut rng) < 5);
+            assert!(GrowingTreeSelection::NewestOrRandom(f64::NAN).choose_index(5, &mut rng) < 5);
+        }
+        assert_eq!(GrowingTreeSelection::NewestOrRandom(7.0).choose_index(5, &mut rng), 4);
+
+        let mut g = small_rect_grid(RowLength(9), ColumnLength(7)).unwrap();
+        growing_tree(&mut g, None, GrowingTreeSelection::NewestOrRandom(f64::NAN), &mut rng);
+        assert_perfect_maze(&g);
+    }
+
+    #[test]
+    fn ellers_generates_perfect_mazes() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for (w, h) in [(1, 1), (1, 6), (6, 1), (2, 2), (9, 7), (12, 20)] {
+            let mut g = small_rect_grid(RowLength(w), ColumnLength(h)).unwrap();
+            ellers(&mut g, &mut rng);
+            assert_perfect_maze(&g);
+        }
+    }
+
+    #[test]
+    fn recursive_division_without_rooms_generates_perfect_mazes() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for room_size in [0, 1] {
+            for (w, h) in [(1, 1), (1, 6), (6, 1), (2, 2), (9, 7), (8, 12)] {
+                let mut g = small_rect_grid(RowLength(w), ColumnLength(h)).unwrap();
+                recursive_division(&mut g, room_size, &mut rng);
+                assert_perfect_maze(&g);
+            }
+        }
+    }
+
+    #[test]
+    fn recursive_division_rooms_stay_connected() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut found_room = false;
+        for _ in 0..20 {
+            let mut g = small_rect_grid(RowLength(10), ColumnLength(10)).unwrap();
+            recursive_division(&mut g, 4, &mut rng);
+
+            let start = g.iter().next().unwrap();
+            let distances = Distances::<SquareCell, u32>::for_grid(&g, start).unwrap();
+            assert_eq!(distances.distances().len(), g.size());
+            // Open rooms are loops, giving more links than a spanning tree.
+            found_room |= g.links_count() > g.size() - 1;
+        }
+        assert!(found_room);
+    }
+
+    #[test]
+    fn recursive_division_fits_the_links_of_small_grids() {
+        // Linking every neighbour of a 15x15 grid would need more links than its u8 index type can count.
+        let mut rng = SmallRng::seed_from_u64(0);
+        for room_size in [1, 4, 15] {
+            for _ in 0..10 {
+                let mut g = small_rect_grid(RowLength(15), ColumnLength(15)).unwrap();
+                recursive_division(&mut g, room_size, &mut rng);
+
+                let start = g.iter().next().unwrap();
+                let distances = Distances::<SquareCell, u32>::for_grid(&g, start).unwrap();
+                assert_eq!(distances.distances().len(), g.size());
+                assert!(g.links_count() <= g.links_capacity());
+            }
+        }
+    }
+
+    #[test]
+    fn recursive_division_adds_walls_when_every_link_fits() {
+        // A 15x15 grid only fits every link with the u16 index type, so the u8 grid is carved instead. Rooms are left
+        // out as the u8 grid only fits a few of them.
+        for seed in 0..10 {
+            let mut small = small_rect_grid(RowLength(15), ColumnLength(15)).unwrap();
+            recursive_division(&mut small, 1, &mut SmallRng::seed_from_u64(seed));
+
+            let mut medium = medium_rect_grid(RowLength(15), ColumnLength(15)).unwrap();
+            let mut steps = vec![];
+            recursive_division(
+                ObservedGrid::new(&mut medium, &mut |step| steps.push(step)),
+                1,
+                &mut SmallRng::seed_from_u64(seed),
+            );
+
+            assert!(steps.iter().any(|e| matches!(e, GenerationEvent::Unlink(_, _))));
+            let sorted_links = |links: Vec<(Cartesian2DCoordinate, Cartesian2DCoordinate)>| {
+                let mut links: Vec<_> = links
+                    .into_iter()
+                    .map(|(a, b)| (cmp::min(a, b), cmp::max(a, b)))
+                    .collect();
+                links.sort();
+                links
+            };
+            assert_eq!(
+                sorted_links(small.iter_links().collect()),
+                sorted_links(medium.iter_links().collect())
+            );
+        }
+    }
+
+    #[test]
+    fn braid_removes_dead_ends() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let dead_ends_count = |g: &SmallRectangularGrid| g.iter().filter(|c| g.links(*c).unwrap().len() == 1).count();
+
+        let mut g = small_rect_grid(RowLength(12), ColumnLength(12)).unwrap();
+        recursive_backtracker(&mut g, None, &mut rng);
+        let perfect_links = g.iter_links().collect::<Vec<_>>();
+        braid(&mut g, 0.0, &mut rng);
+        assert_eq!(g.iter_links().collect::<Vec<_>>(), perfect_links);
+
+        let dead_ends_before = dead_ends_count(&g);
+        braid(&mut g, 1.0, &mut rng);
+        assert!(dead_ends_before > 0);
+        assert_eq!(dead_ends_count(&g), 0);
+        assert!(g.links_count() > g.size() - 1);
+    }
+
+    #[test]
+    fn braid_stops_at_the_links_capacity_of_small_grids() {
+        // A perfect 16x15 maze has 239 links, leaving the u8 index type room for only 15 more.
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..10 {
+            let mut g = small_rect_grid(RowLength(16), ColumnLength(15)).unwrap();
+            recursive_backtracker(&mut g, None, &mut rng);
+            braid(&mut g, 1.0, &mut rng);
+
+            assert_eq!(g.links_count(), g.links_capacity());
+        }
+    }
+
+    #[test]
+    fn braid_leaves_masked_cells_unlinked() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut mask_image = image::GrayImage::from_pixel(6, 6, image::Luma([255]));
+        mask_image.put_pixel(2, 2, image::Luma([0]));
+        let mask = BinaryMask2D::from_image(&image::DynamicImage::ImageLuma8(mask_image));
+
+        let mut g = small_rect_grid(RowLength(6), ColumnLength(6)).unwrap();
+        recursive_backtracker(&mut g, Some(&mask), &mut rng);
+        braid(&mut g, 1.0, &mut rng);
+        assert!(g.links(Cartesian2DCoordinate::new(2, 2)).unwrap().is_empty());
+    }
+
+    /// Check that every cell over a tunnel is a straight corridor across it, returning the number of tunnels.
+    fn assert_weave_crossings(g: &WeaveGrid) -> usize {
+        let mut tunnels_count = 0;
+        for cell in g.iter() {
+            let under_north_south = WeaveCell::is_tunnelled_under(g, cell, CompassPrimary::North);
+            let under_east_west = WeaveCell::is_tunnelled_under(g, cell, CompassPrimary::East);
+            let (across, in_line) = match (under_north_south, under_east_west) {
+                (false, false) => continue,
+                (true, false) => (
+                    [CompassPrimary::East, CompassPrimary::West],
+                    [CompassPrimary::North, CompassPrimary::South],
+                ),
+                (false, true) => (
+                    [CompassPrimary::North, CompassPrimary::South],
+                    [CompassPrimary::East, CompassPrimary::West],
+                ),
+                (true, true) => panic!("Two tunnels under {:?}", cell),
+            };
+            assert!(across.iter().all(|dir| WeaveCell::is_passage_towards(g, cell, *dir)));
+            assert!(!in_line.iter().any(|dir| g.is_neighbour_linked(cell, *dir)));
+            assert_eq!(g.links(cell).unwrap().len(), 2);
+            tunnels_count += 1;
+        }
+        tunnels_count
+    }
+
+    #[test]
+    fn weave_generators_tunnel_under_corridors() {
+        let generators: [fn(&mut WeaveGrid, &mut SmallRng); 6] = [
+            |g, rng| recursive_backtracker(g, None, rng),
+            |g, rng| aldous_broder(g, None, rng),
+            |g, rng| hunt_and_kill(g, None, rng),
+            |g, rng| simplified_prims(g, None, rng),
+            |g, rng| growing_tree(g, None, GrowingTreeSelection::Newest, rng),
+            |g, rng| wilson(g, None, rng),
+        ];
+        for generator in generators.iter() {
+            let mut rng = SmallRng::seed_from_u64(0);
+            let mut g = weave_grid(RowLength(15), ColumnLength(15)).unwrap();
+            generator(&mut g, &mut rng);
+            assert_perfect_maze(&g);
+            assert!(assert_weave_crossings(&g) > 0);
+        }
+    }
+
+    #[test]
+    fn braiding_and_rebuilding_walls_keep_weave_crossings() {
+        for seed in 0..20 {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let mut g = weave_grid(RowLength(10), ColumnLength(10)).unwrap();
+            recursive_backtracker(&mut g, None, &mut rng);
+            let tunnels_count = assert_weave_crossings(&g);
+
+            braid(&mut g, 1.0, &mut rng);
+            assert!(assert_weave_crossings(&g) >= tunnels_count);
+            rebuild_random_walls(&mut g, 30, &mut rng);
+            assert_weave_crossings(&g);
+            rebuild_random_walls(&mut g, 30, &mut rng);
+            braid(&mut g, 0.5, &mut rng);
+            assert_weave_crossings(&g);
+        }
+    }
+
+    #[test]
+    fn replaying_generation_steps_rebuilds_the_maze() {
+        type ObservedSmallRectangularGrid<'a> = ObservedGrid<'a, u8, SquareCell, RectGridIterators>;
+        let generators: [fn(ObservedSmallRectangularGrid, &mut SmallRng); 8] = [
+            |g, rng| binary_tree(g, rng),
+            |g, rng| ellers(g, rng),
+            |g, rng| recursive_division(g, 3, rng),
+            |g, rng| wilson(g, None, rng),
+            |g, rng| hunt_and_kill(g, None, rng),
+            |g, rng| recursive_backtracker(g, None, rng),
+            |g, rng| kruskal(g, None, rng),
+            |mut g, rng| {
+                growing_tree(g.reborrow(), None, GrowingTreeSelection::Random, rng);
+                braid(g, 0.5, rng);
+            },
+        ];
+        for generator in generators.iter() {
+            let mut rng = SmallRng::seed_from_u64(0);
+            let mut g = small_rect_grid(RowLength(9), ColumnLength(8)).unwrap();
+            let mut steps = vec![];
+            generator(ObservedGrid::new(&mut g, &mut |step| steps.push(step)), &mut rng);
+
+            let mut replayed = small_rect_grid(RowLength(9), ColumnLength(8)).unwrap();
+            for event in steps {
+                event.apply(&mut replayed);
+            }
+            let sorted_links = |g: &SmallRectangularGrid| {
+                let mut links: Vec<_> = g.iter_links().map(|(a, b)| (cmp::min(a, b), cmp::max(a, b))).collect();
+                links.sort();
+                links
+            };
+            assert_eq!(sorted_links(&replayed), sorted_links(&g));
+        }
+    }
+
+    #[test]
+    fn generation_steps_wait_to_be_asked_for() {
+        let observed_steps = Arc::new(AtomicUsize::new(0));
+        let generation_observed_steps = Arc::clone(&observed_steps);
+        let mut steps = GenerationSteps::new(move |observer| {
+            let mut g = small_rect_grid(RowLength(9), ColumnLength(8)).unwrap();
+            let mut count_step = |step| {
+                generation_observed_steps.fetch_add(1, Ordering::SeqCst);
+                observer(step);
+            };
+            wilson(
+                ObservedGrid::new(&mut g, &mut count_step),
+                None,
+                &mut SmallRng::seed_from_u64(0),
+            );
+        });
+
+        let first_steps: Vec<_> = steps.by_ref().take(5).collect();
+        assert_eq!(first_steps.len(), 5);
+        // The generation may have reached the next step, but goes no further until it is asked for.
+        thread::sleep(std::time::Duration::from_millis(20));
+        assert!(observed_steps.load(Ordering::SeqCst) <= 6);
+
+        let mut expected_steps = vec![];
+        let mut g = small_rect_grid(RowLength(9), ColumnLength(8)).unwrap();
+        wilson(
+            ObservedGrid::new(&mut g, &mut |step| expected_steps.push(step)),
+            None,
+            &mut SmallRng::seed_from_u64(0),
+        );
+        let all_steps: Vec<_> = first_steps.into_iter().chain(steps).collect();
+        assert_eq!(all_steps, expected_steps);
+    }
+
+    #[test]
+    #[should_panic(expected = "generation failed")]
+    fn generation_steps_pass_on_generation_panics() {
+        let steps = GenerationSteps::<Cartesian2DCoordinate>::new(|_| panic!("generation failed"));
+        let _ = steps.count();
+    }
+
+    #[test]
+    fn observed_grids_report_visits_backtracks_and_erased_loops() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut g = small_rect_grid(RowLength(6), ColumnLength(6)).unwrap();
+        let mut steps = vec![];
+        recursive_backtracker(ObservedGrid::new(&mut g, &mut |step| steps.push(step)), None, &mut rng);
+        let count = |is_event: fn(&GenerationEvent<Cartesian2DCoordinate>) -> bool| {
+            steps.iter().filter(|e| is_event(e)).count()
+        };
+        assert_eq!(count(|e| matches!(e, GenerationEvent::Visit(_))), g.size());
+        assert_eq!(count(|e| matches!(e, GenerationEvent::Link(_, _))), g.size() - 1);
+        // Every cell is pushed onto and popped off the backtracker's stack once.
+        assert_eq!(count(|e| matches!(e, GenerationEvent::Backtrack(_))), g.size());
+
+        let mut g = small_rect_grid(RowLength(10), ColumnLength(10)).unwrap();
+        let mut steps = vec![];
+        wilson(ObservedGrid::new(&mut g, &mut |step| steps.push(step)), None, &mut rng);
+        assert!(steps
+            .iter()
+            .any(|e| matches!(e, GenerationEvent::EraseLoop(cells) if !cells.is_empty())));
+
+        // Every generator run on the grid is observed, e.g. rebuilding walls after generating the maze.
+        let mut g = small_rect_grid(RowLength(6), ColumnLength(6)).unwrap();
+        let mut steps = vec![];
+        let mut record_step = |step| steps.push(step);
+        let mut observed = ObservedGrid::new(&mut g, &mut record_step);
+        recursive_backtracker(observed.reborrow(), None, &mut rng);
+        rebuild_random_walls(observed, 3, &mut rng);
+        assert_eq!(
+            steps
+                .iter()
+                .filter(|e| matches!(e, GenerationEvent::Unlink(_, _)))
+                .count(),
+            3
+        );
+    }
+}
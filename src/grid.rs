@@ -1,6 +1,6 @@
 use crate::{
     cells::{Cell, Coordinate},
-    grid_traits::{GridCoordinates, GridDimensions, GridDisplay, GridIterators},
+    grid_traits::{GridCoordinates, GridDimensions, GridDisplay, GridIterators, GridLinks},
     units::{ColumnLength, ColumnsCount, EdgesCount, NodesCount, RowLength, RowsCount},
 };
 
@@ -9,8 +9,17 @@ use petgraph::{graph, Graph, Undirected};
 use rand::rngs::SmallRng;
 use std::{fmt, marker::PhantomData, rc::Rc, slice};
 
+/// The cost of moving into a cell unless it is given another weight.
+pub const DEFAULT_CELL_WEIGHT: u32 = 1;
+/// The extra cost of moving through a link between two cells unless it is given another weight.
+pub const DEFAULT_LINK_WEIGHT: u32 = 0;
+
+/// The cells of a grid and the links (passages) between them.
+///
+/// Cells and links also have weights, the cost of moving through them, which default to each step between two cells
+/// costing 1. A step into a cell costs the cell's weight plus the weight of the link it goes through.
 pub struct Grid<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> {
-    graph: Graph<(), (), Undirected, GridIndexType>,
+    graph: Graph<u32, u32, Undirected, GridIndexType>,
     dimensions: Rc<dyn GridDimensions>,
     coordinates: Box<dyn GridCoordinates<CellT>>,
     iterators: Iters, /* cannot be trait without boxing the CellIter/BatchIter types - type CellIter: Box<Iterator...> */
@@ -22,6 +31,8 @@ pub struct Grid<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<Cell
 pub enum CellLinkError {
     InvalidGridCoordinate,
     SelfLink,
+    /// The grid index type cannot count any more links, e.g. on a large `small_rect_grid`.
+    TooManyLinks,
 }
 
 impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> fmt::Debug
@@ -56,7 +67,7 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
             cell_type: PhantomData,
         };
         for _ in 0..nodes {
-            let _ = grid.graph.add_node(());
+            let _ = grid.graph.add_node(DEFAULT_CELL_WEIGHT);
         }
 
         grid
@@ -94,6 +105,12 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
         self.graph.edge_count()
     }
 
+    /// The most links the grid index type can count, the largest index being reserved by petgraph.
+    #[inline]
+    pub fn links_capacity(&self) -> usize {
+        <GridIndexType as IndexType>::max().index()
+    }
+
     #[inline]
     pub fn rows(&self) -> RowsCount {
         self.dimensions.rows()
@@ -131,7 +148,13 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
             let b_index_opt = self.grid_coordinate_graph_index(b);
             match (a_index_opt, b_index_opt) {
                 (Some(a_index), Some(b_index)) => {
-                    let _ = self.graph.update_edge(a_index, b_index, ());
+                    // Relinking keeps any weight the link already has.
+                    if self.graph.find_edge(a_index, b_index).is_none() {
+                        if self.links_count() >= self.links_capacity() {
+                            return Err(CellLinkError::TooManyLinks);
+                        }
+                        let _ = self.graph.add_edge(a_index, b_index, DEFAULT_LINK_WEIGHT);
+                    }
                     Ok(())
                 }
                 _ => Err(CellLinkError::InvalidGridCoordinate),
@@ -159,6 +182,77 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
         false
     }
 
+    /// Link every cell to all of its neighbours, replacing any existing links.
+    ///
+    /// Much cheaper than calling `link` for each pair of neighbours as there is no search for an existing link.
+    ///
+    /// Fails, leaving the grid unchanged, if there are more links than the grid index type can count, e.g. with a
+    /// large `small_rect_grid`.
+    pub fn link_all_neighbours(&mut self) -> Result<(), CellLinkError> {
+        // Each pair of neighbours is counted from both sides.
+        let neighbour_pairs_count = self.iter().map(|coord| self.neighbours(coord).len()).sum::<usize>() / 2;
+        if neighbour_pairs_count > self.links_capacity() {
+            return Err(CellLinkError::TooManyLinks);
+        }
+
+        self.graph.clear_edges();
+
+        for coord in self.iter() {
+            if let Some(index) = self.grid_coordinate_graph_index(coord) {
+                // Only link to the higher indexed neighbours so that each pair is linked once.
+                for neighbour in self.neighbours(coord).iter() {
+                    if let Some(neighbour_index) = self.grid_coordinate_graph_index(*neighbour) {
+                        if neighbour_index > index {
+                            self.graph.add_edge(index, neighbour_index, DEFAULT_LINK_WEIGHT);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The cost of moving into a cell, `None` if the grid coordinate is invalid.
+    pub fn cell_weight(&self, coord: CellT::Coord) -> Option<u32> {
+        self.grid_coordinate_graph_index(coord).map(|index| self.graph[index])
+    }
+
+    /// Set the cost of moving into a cell, e.g. higher for mud or lava.
+    /// Returns false if the grid coordinate is invalid.
+    pub fn set_cell_weight(&mut self, coord: CellT::Coord, weight: u32) -> bool {
+        if let Some(index) = self.grid_coordinate_graph_index(coord) {
+            self.graph[index] = weight;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The extra cost of moving through the link between two cells, `None` if they are not linked.
+    pub fn link_weight(&self, a: CellT::Coord, b: CellT::Coord) -> Option<u32> {
+        self.find_link(a, b).map(|edge_index| self.graph[edge_index])
+    }
+
+    /// Set the extra cost of moving through the link between two cells, e.g. for a door.
+    /// The weight is lost if the cells are unlinked. Returns false if the cells are not linked.
+    pub fn set_link_weight(&mut self, a: CellT::Coord, b: CellT::Coord, weight: u32) -> bool {
+        if let Some(edge_index) = self.find_link(a, b) {
+            self.graph[edge_index] = weight;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The cost of moving from one cell into a linked cell: the weight of the cell moved into plus the weight of the
+    /// link. `None` if the cells are not linked.
+    pub fn step_cost(&self, from: CellT::Coord, to: CellT::Coord) -> Option<u32> {
+        let link_weight = self.link_weight(from, to)?;
+        let cell_weight = self.cell_weight(to)?;
+        Some(cell_weight.saturating_add(link_weight))
+    }
+
     /// Cell nodes that are linked to a particular node by a passage.
     pub fn links(&self, coord: CellT::Coord) -> Option<CellT::CoordinateSmallVec> {
         if let Some(graph_node_index) = self.grid_coordinate_graph_index(coord) {
@@ -175,24 +269,11 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
 
     /// Cell nodes that are to the North, South, East or West of a particular node, but not
     /// necessarily linked by a passage.
+    ///
+    /// Cell types whose neighbours depend on the passages already carved, e.g. weave cells, decide them, see
+    /// `Cell::neighbours`.
     pub fn neighbours(&self, coord: CellT::Coord) -> CellT::CoordinateSmallVec {
-        let all_dirs: CellT::DirectionSmallVec = CellT::offset_directions(Some(coord), self.dimensions());
-        all_dirs
-            .iter()
-            .cloned()
-            .map(|dir: CellT::Direction| CellT::offset_coordinate(coord, dir, self.dimensions()))
-            .filter_map(|adjacent_coord_opt: Option<CellT::Coord>| -> Option<CellT::Coord> {
-                if let Some(adjacent_coord) = adjacent_coord_opt {
-                    if self.is_valid_coordinate(adjacent_coord) {
-                        adjacent_coord_opt
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect::<CellT::CoordinateSmallVec>()
+        CellT::neighbours(coord, self)
     }
 
     pub fn neighbours_at_directions(
@@ -219,18 +300,12 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
 
     /// Are two cells in the grid linked?
     pub fn is_linked(&self, a: CellT::Coord, b: CellT::Coord) -> bool {
-        let a_index_opt = self.grid_coordinate_graph_index(a);
-        let b_index_opt = self.grid_coordinate_graph_index(b);
-        if let (Some(a_index), Some(b_index)) = (a_index_opt, b_index_opt) {
-            self.graph.find_edge(a_index, b_index).is_some()
-        } else {
-            false
-        }
+        self.find_link(a, b).is_some()
     }
 
     pub fn is_neighbour_linked(&self, coord: CellT::Coord, direction: CellT::Direction) -> bool {
         self.neighbour_at_direction(coord, direction)
-            .map_or(false, |neighbour_coord| self.is_linked(coord, neighbour_coord))
+            .is_some_and(|neighbour_coord| self.is_linked(coord, neighbour_coord))
     }
 
     /// Convert a grid coordinate to a one dimensional index in the range 0...grid.size().
@@ -255,7 +330,7 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
         self.iterators.iter_column(&self.dimensions)
     }
 
-    pub fn iter_links(&self) -> LinksIter<CellT, GridIndexType> {
+    pub fn iter_links(&self) -> LinksIter<'_, CellT, GridIndexType> {
         LinksIter {
             graph_edge_iter: self.graph.raw_edges().iter(),
             dimensions: self.dimensions(),
@@ -272,7 +347,13 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
     #[allow(dead_code)] // for now
     fn is_neighbour(&self, a: CellT::Coord, b: CellT::Coord) -> bool {
         // For .iter Coord satifies `Deref<Target=[Self::Coord]>`
-        self.neighbours(a).iter().any(|&coord| coord == b)
+        self.neighbours(a).contains(&b)
+    }
+
+    fn find_link(&self, a: CellT::Coord, b: CellT::Coord) -> Option<graph::EdgeIndex<GridIndexType>> {
+        let a_index = self.grid_coordinate_graph_index(a)?;
+        let b_index = self.grid_coordinate_graph_index(b)?;
+        self.graph.find_edge(a_index, b_index)
     }
 
     /// Convert a grid coordinate into petgraph nodeindex
@@ -284,8 +365,24 @@ impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> Grid<Gr
     }
 }
 
+impl<GridIndexType: IndexType, CellT: Cell, Iters: GridIterators<CellT>> GridLinks<CellT>
+    for Grid<GridIndexType, CellT, Iters>
+{
+    fn dimensions(&self) -> &dyn GridDimensions {
+        Grid::dimensions(self)
+    }
+
+    fn neighbour_at_direction(&self, coord: CellT::Coord, direction: CellT::Direction) -> Option<CellT::Coord> {
+        Grid::neighbour_at_direction(self, coord, direction)
+    }
+
+    fn is_linked(&self, a: CellT::Coord, b: CellT::Coord) -> bool {
+        Grid::is_linked(self, a, b)
+    }
+}
+
 pub struct LinksIter<'a, CellT: Cell, GridIndexType: IndexType> {
-    graph_edge_iter: slice::Iter<'a, graph::Edge<(), GridIndexType>>,
+    graph_edge_iter: slice::Iter<'a, graph::Edge<u32, GridIndexType>>,
     dimensions: &'a dyn GridDimensions,
     cell_type: PhantomData<CellT>,
 }
@@ -332,13 +429,14 @@ impl<CellT: Cell, GridIndexType: IndexType> fmt::Debug for LinksIter<'_, CellT,
 mod tests {
 
     use super::*;
-    use crate::cells::{Cartesian2DCoordinate, CompassPrimary};
-    use crate::grids::{small_rect_grid, SmallRectangularGrid};
+    use crate::cells::{Cartesian2DCoordinate, ClockDirection, CompassHex, CompassPrimary, WeaveCell};
+    use crate::grids::{
+        hex_grid, polar_grid, small_rect_grid, triangle_grid, weave_grid, SmallRectangularGrid, WeaveGrid,
+    };
 
     use itertools::Itertools; // a trait
     use rand::{rngs::SmallRng, SeedableRng};
     use smallvec::SmallVec;
-    use std::u32;
 
     fn small_grid(w: usize, h: usize) -> SmallRectangularGrid {
         small_rect_grid(RowLength(w), ColumnLength(h)).expect("grid dimensions too large for small grid")
@@ -360,8 +458,7 @@ mod tests {
 
         let check_expected_neighbours = |coord, expected_neighbours: &[Cartesian2DCoordinate]| {
             let node_indices: Vec<Cartesian2DCoordinate> = g.neighbours(coord).iter().cloned().sorted().collect();
-            let expected_indices: Vec<Cartesian2DCoordinate> =
-                expected_neighbours.into_iter().cloned().sorted().collect();
+            let expected_indices: Vec<Cartesian2DCoordinate> = expected_neighbours.iter().cloned().sorted().collect();
             assert_eq!(node_indices, expected_indices);
         };
         let gc = |x, y| Cartesian2DCoordinate::new(x, y);
@@ -457,11 +554,8 @@ mod tests {
             gc(1, 2),
             gc(2, 2),
         ];
-        let indices: Vec<Option<usize>> = coords
-            .into_iter()
-            .map(|coord| g.grid_coordinate_to_index(*coord))
-            .collect();
-        let expected = (0..9).map(|n| Some(n)).collect::<Vec<Option<usize>>>();
+        let indices: Vec<Option<usize>> = coords.iter().map(|coord| g.grid_coordinate_to_index(*coord)).collect();
+        let expected = (0..9).map(Some).collect::<Vec<Option<usize>>>();
         assert_eq!(expected, indices);
 
         assert_eq!(g.grid_coordinate_to_index(gc(2, 3)), None);
@@ -661,6 +755,30 @@ mod tests {
         assert_eq!(link_result, Err(CellLinkError::InvalidGridCoordinate));
     }
 
+    #[test]
+    fn no_more_links_than_the_index_type_can_count() {
+        // 15x15 cells have 420 pairs of neighbours, far more than a u8 can index.
+        let mut g = small_grid(15, 15);
+        assert_eq!(g.link_all_neighbours(), Err(CellLinkError::TooManyLinks));
+        assert_eq!(g.links_count(), 0);
+
+        let pairs = g
+            .iter()
+            .flat_map(|coord| g.neighbours(coord).into_iter().map(move |neighbour| (coord, neighbour)))
+            .filter(|(a, b)| a < b)
+            .collect::<Vec<_>>();
+        let (linked, too_many) = pairs.split_at(g.links_capacity());
+        for (a, b) in linked {
+            g.link(*a, *b).expect("link failed");
+        }
+        let (a, b) = too_many[0];
+        assert_eq!(g.link(a, b), Err(CellLinkError::TooManyLinks));
+        // Relinking cells that are already linked needs no new link.
+        let (a, b) = linked[0];
+        assert_eq!(g.link(a, b), Ok(()));
+        assert_eq!(g.links_count(), g.links_capacity());
+    }
+
     #[test]
     fn no_parallel_duplicated_linked_cells() {
         let mut g = small_grid(4, 4);
@@ -675,4 +793,248 @@ mod tests {
         assert_smallvec_eq!(g.links(a).unwrap(), &[]);
         assert_smallvec_eq!(g.links(b).unwrap(), &[]);
     }
+
+    #[test]
+    fn polar_grid_row_lengths() {
+        let g = polar_grid(RowsCount(4)).unwrap();
+        let row_lengths: Vec<usize> = g.iter_row().map(|row| row.len()).collect();
+        assert_eq!(row_lengths, vec![1, 6, 12, 24]);
+        assert_eq!(g.size(), 43);
+    }
+
+    #[test]
+    fn polar_coordinate_index_round_trip() {
+        let g = polar_grid(RowsCount(5)).unwrap();
+        let coords: Vec<Cartesian2DCoordinate> = g.iter().collect();
+        assert_eq!(coords.len(), g.size());
+        for (index, coord) in coords.iter().enumerate() {
+            assert!(g.is_valid_coordinate(*coord));
+            assert_eq!(g.grid_coordinate_to_index(*coord), Some(index));
+        }
+        assert_eq!(coords[1], Cartesian2DCoordinate::new(0, 1));
+        assert_eq!(coords[7], Cartesian2DCoordinate::new(0, 2));
+    }
+
+    #[test]
+    fn polar_neighbour_cells() {
+        let g = polar_grid(RowsCount(3)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let sorted_neighbours =
+            |coord| -> Vec<Cartesian2DCoordinate> { g.neighbours(coord).iter().cloned().sorted().collect() };
+
+        // The centre is next to every cell on the first ring
+        assert_eq!(
+            sorted_neighbours(gc(0, 0)),
+            (0..6).map(|x| gc(x, 1)).collect::<Vec<_>>()
+        );
+        // First ring cells wrap around and subdivide outwards
+        assert_eq!(
+            sorted_neighbours(gc(0, 1)),
+            vec![gc(0, 0), gc(0, 2), gc(1, 1), gc(1, 2), gc(5, 1)]
+        );
+        // The outermost ring has no outward neighbours
+        assert_eq!(sorted_neighbours(gc(11, 2)), vec![gc(0, 2), gc(5, 1), gc(10, 2)]);
+    }
+
+    #[test]
+    fn polar_clockwise_wraps_around_ring() {
+        let g = polar_grid(RowsCount(3)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        assert_eq!(
+            g.neighbour_at_direction(gc(11, 2), ClockDirection::Clockwise),
+            Some(gc(0, 2))
+        );
+        assert_eq!(
+            g.neighbour_at_direction(gc(0, 2), ClockDirection::CounterClockwise),
+            Some(gc(11, 2))
+        );
+        assert_eq!(
+            g.neighbour_at_direction(gc(3, 2), ClockDirection::Inward),
+            Some(gc(1, 1))
+        );
+        assert_eq!(
+            g.neighbour_at_direction(gc(3, 1), ClockDirection::Outward(1)),
+            Some(gc(7, 2))
+        );
+        assert_eq!(g.neighbour_at_direction(gc(3, 1), ClockDirection::Outward(2)), None);
+        assert_eq!(g.neighbour_at_direction(gc(0, 0), ClockDirection::Clockwise), None);
+        assert_eq!(g.neighbour_at_direction(gc(0, 0), ClockDirection::Inward), None);
+    }
+
+    #[test]
+    fn polar_column_iter() {
+        let g = polar_grid(RowsCount(3)).unwrap();
+        let columns = g.iter_column().collect::<Vec<Vec<Cartesian2DCoordinate>>>();
+        assert_eq!(columns.len(), 12);
+        assert_eq!(
+            columns[7],
+            &[
+                Cartesian2DCoordinate::new(7, 2),
+                Cartesian2DCoordinate::new(3, 1),
+                Cartesian2DCoordinate::new(0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn hex_neighbour_cells() {
+        let g = hex_grid(RowLength(4), ColumnLength(4)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let sorted_neighbours = |coord| -> Vec<Cartesian2DCoordinate> {
+            let mut neighbours: Vec<Cartesian2DCoordinate> = g.neighbours(coord).iter().cloned().collect();
+            neighbours.sort();
+            neighbours
+        };
+
+        assert_eq!(sorted_neighbours(gc(0, 0)), vec![gc(0, 1), gc(1, 0)]);
+        // Even column, diagonals are on the row above and the same row
+        assert_eq!(
+            sorted_neighbours(gc(2, 1)),
+            vec![gc(1, 0), gc(1, 1), gc(2, 0), gc(2, 2), gc(3, 0), gc(3, 1)]
+        );
+        // Odd column, diagonals are on the same row and the row below
+        assert_eq!(
+            sorted_neighbours(gc(1, 1)),
+            vec![gc(0, 1), gc(0, 2), gc(1, 0), gc(1, 2), gc(2, 1), gc(2, 2)]
+        );
+        assert_eq!(sorted_neighbours(gc(3, 3)), vec![gc(2, 3), gc(3, 2)]);
+    }
+
+    #[test]
+    fn hex_neighbour_at_dir() {
+        let g = hex_grid(RowLength(3), ColumnLength(3)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        assert_eq!(g.neighbour_at_direction(gc(0, 0), CompassHex::North), None);
+        assert_eq!(g.neighbour_at_direction(gc(0, 0), CompassHex::NorthEast), None);
+        assert_eq!(
+            g.neighbour_at_direction(gc(0, 0), CompassHex::SouthEast),
+            Some(gc(1, 0))
+        );
+        assert_eq!(
+            g.neighbour_at_direction(gc(1, 0), CompassHex::NorthWest),
+            Some(gc(0, 0))
+        );
+        assert_eq!(
+            g.neighbour_at_direction(gc(1, 0), CompassHex::SouthWest),
+            Some(gc(0, 1))
+        );
+        assert_eq!(g.neighbour_at_direction(gc(1, 2), CompassHex::SouthEast), None);
+        assert_eq!(g.neighbour_at_direction(gc(2, 2), CompassHex::South), None);
+    }
+
+    #[test]
+    fn triangle_neighbour_cells() {
+        let g = triangle_grid(RowLength(3), ColumnLength(2)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let sorted_neighbours = |coord| -> Vec<Cartesian2DCoordinate> {
+            let mut neighbours: Vec<Cartesian2DCoordinate> = g.neighbours(coord).iter().cloned().collect();
+            neighbours.sort();
+            neighbours
+        };
+
+        // Upright triangles connect south, downward ones north.
+        assert_eq!(sorted_neighbours(gc(0, 0)), vec![gc(0, 1), gc(1, 0)]);
+        assert_eq!(sorted_neighbours(gc(1, 0)), vec![gc(0, 0), gc(2, 0)]);
+        assert_eq!(sorted_neighbours(gc(1, 1)), vec![gc(0, 1), gc(2, 1)]);
+        assert_eq!(sorted_neighbours(gc(0, 1)), vec![gc(0, 0), gc(1, 1)]);
+        assert_eq!(g.neighbour_at_direction(gc(0, 0), CompassPrimary::North), None);
+        assert_eq!(g.neighbour_at_direction(gc(1, 0), CompassPrimary::South), None);
+    }
+
+    #[test]
+    fn triangle_grids_need_rows_two_cells_wide() {
+        // A single column of triangles only joins them in pairs, so could never be one maze.
+        assert!(triangle_grid(RowLength(1), ColumnLength(1)).is_none());
+        assert!(triangle_grid(RowLength(1), ColumnLength(4)).is_none());
+        assert!(triangle_grid(RowLength(2), ColumnLength(4)).is_some());
+    }
+
+    #[test]
+    fn link_all_neighbours_links_every_adjacent_pair() {
+        let mut g = small_grid(4, 3);
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(0, 0), gc(1, 0)).unwrap();
+        g.link_all_neighbours().unwrap();
+
+        // 3 horizontal links in each of the 3 rows and 4 vertical links between each of the 2 pairs of rows
+        assert_eq!(g.links_count(), 3 * 3 + 4 * 2);
+        for coord in g.iter() {
+            let mut links: Vec<Cartesian2DCoordinate> = g.links(coord).unwrap().iter().cloned().collect();
+            let mut neighbours: Vec<Cartesian2DCoordinate> = g.neighbours(coord).iter().cloned().collect();
+            links.sort();
+            neighbours.sort();
+            assert_eq!(links, neighbours);
+        }
+
+        let mut p = polar_grid(RowsCount(4)).unwrap();
+        p.link_all_neighbours().unwrap();
+        for coord in p.iter() {
+            assert_eq!(p.links(coord).unwrap().len(), p.neighbours(coord).len());
+        }
+    }
+
+    #[test]
+    fn cell_and_link_weights() {
+        let mut g = small_grid(3, 3);
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let out_of_grid = gc(3, 0);
+        assert_eq!(g.cell_weight(gc(1, 1)), Some(DEFAULT_CELL_WEIGHT));
+        assert_eq!(g.cell_weight(out_of_grid), None);
+        assert!(g.set_cell_weight(gc(1, 1), 5));
+        assert!(!g.set_cell_weight(out_of_grid, 5));
+        assert_eq!(g.cell_weight(gc(1, 1)), Some(5));
+
+        assert_eq!(g.link_weight(gc(0, 1), gc(1, 1)), None);
+        assert!(!g.set_link_weight(gc(0, 1), gc(1, 1), 3));
+        g.link(gc(0, 1), gc(1, 1)).unwrap();
+        assert_eq!(g.link_weight(gc(0, 1), gc(1, 1)), Some(DEFAULT_LINK_WEIGHT));
+        assert!(g.set_link_weight(gc(1, 1), gc(0, 1), 3));
+        // Relinking keeps the weight.
+        g.link(gc(0, 1), gc(1, 1)).unwrap();
+        assert_eq!(g.link_weight(gc(0, 1), gc(1, 1)), Some(3));
+
+        assert_eq!(g.step_cost(gc(0, 1), gc(1, 1)), Some(5 + 3));
+        assert_eq!(g.step_cost(gc(1, 1), gc(0, 1)), Some(DEFAULT_CELL_WEIGHT + 3));
+        assert_eq!(g.step_cost(gc(1, 1), gc(2, 1)), None);
+
+        g.unlink(gc(0, 1), gc(1, 1));
+        g.link(gc(0, 1), gc(1, 1)).unwrap();
+        assert_eq!(g.link_weight(gc(0, 1), gc(1, 1)), Some(DEFAULT_LINK_WEIGHT));
+        // Cell weights outlive the links.
+        g.link_all_neighbours().unwrap();
+        assert_eq!(g.cell_weight(gc(1, 1)), Some(5));
+    }
+
+    #[test]
+    fn weave_neighbours_report_crossings() {
+        let mut g = weave_grid(RowLength(3), ColumnLength(3)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let sorted_neighbours = |g: &WeaveGrid, coord| -> Vec<Cartesian2DCoordinate> {
+            g.neighbours(coord).iter().cloned().sorted().collect()
+        };
+        assert_eq!(sorted_neighbours(&g, gc(0, 1)), vec![gc(0, 0), gc(0, 2), gc(1, 1)]);
+
+        // A north-south corridor through the middle can be tunnelled under from the west.
+        g.link(gc(1, 0), gc(1, 1)).unwrap();
+        g.link(gc(1, 1), gc(1, 2)).unwrap();
+        assert_eq!(
+            sorted_neighbours(&g, gc(0, 1)),
+            vec![gc(0, 0), gc(0, 2), gc(1, 1), gc(2, 1)]
+        );
+
+        g.link(gc(0, 1), gc(2, 1)).unwrap();
+        assert!(WeaveCell::is_tunnelled_under(&g, gc(1, 1), CompassPrimary::East));
+        assert!(!WeaveCell::is_tunnelled_under(&g, gc(1, 1), CompassPrimary::North));
+        assert!(WeaveCell::is_passage_towards(&g, gc(0, 1), CompassPrimary::East));
+        assert!(!WeaveCell::is_passage_towards(&g, gc(1, 1), CompassPrimary::West));
+        // The tunnel takes up the corridor's west and east sides.
+        assert_eq!(sorted_neighbours(&g, gc(0, 1)), vec![gc(0, 0), gc(0, 2), gc(2, 1)]);
+        assert_eq!(sorted_neighbours(&g, gc(1, 1)), vec![gc(1, 0), gc(1, 2)]);
+        // Only one tunnel fits under a cell.
+        assert_eq!(sorted_neighbours(&g, gc(1, 0)), vec![gc(0, 0), gc(1, 1), gc(2, 0)]);
+        // The corridor stays over the tunnel, but the tunnel itself can be walled up.
+        assert!(!WeaveCell::can_unlink(gc(1, 0), gc(1, 1), &g));
+        assert!(!WeaveCell::can_unlink(gc(1, 2), gc(1, 1), &g));
+        assert!(WeaveCell::can_unlink(gc(0, 1), gc(2, 1), &g));
+    }
 }
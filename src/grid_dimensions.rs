@@ -61,6 +61,7 @@ impl GridDimensions for RectGridDimensions {
 #[derive(Debug, Clone)]
 pub struct PolarGridDimensions {
     row_cell_counts: Vec<usize>,
+    // Number of nodes in all the rows before a row index, with one extra trailing entry holding the grid size.
     per_row_cumulative_node_count: Vec<NodesCount>,
     rows: RowsCount, // height (y coord) of the grid
     size: NodesCount,
@@ -69,50 +70,55 @@ pub struct PolarGridDimensions {
 impl PolarGridDimensions {
     pub fn new(rows: RowsCount) -> PolarGridDimensions {
         let RowsCount(row_count) = rows;
-        let mut cell_counts = Vec::with_capacity(row_count);
+        let mut cell_counts: Vec<usize> = Vec::with_capacity(row_count);
 
         use std::f32::consts::PI;
 
-        // working with a unit circle that can be scaled later
-        let row_height = 1.0 / row_count as f32;
-        // The circle centre with one cell only that can be accessed.
-        cell_counts[0] = 1;
+        if row_count > 0 {
+            // working with a unit circle that can be scaled later
+            let row_height = 1.0 / row_count as f32;
+            // The circle centre with one cell only that can be accessed.
+            cell_counts.push(1);
 
-        for y in 1..row_count {
-            // radius of how far from centre the row inner boundary is
-            let inner_radius = y as f32 * row_height;
+            for y in 1..row_count {
+                // radius of how far from centre the row inner boundary is
+                let inner_radius = y as f32 * row_height;
 
-            // length of inner boundary
-            let circumference = 2.0 * PI * inner_radius;
+                // length of inner boundary
+                let circumference = 2.0 * PI * inner_radius;
 
-            let previous_row_cell_count: usize = cell_counts[y - 1];
+                let previous_row_cell_count: usize = cell_counts[y - 1];
 
-            // If we were to have as many cells as the previous inner row then the
-            // cells must be this wide.
-            // Tells us how wide each cell would be in this row if we don’t subdivide
-            // Our ideal cell width is the same as the height of the row
-            let estimated_cell_width = circumference / previous_row_cell_count as f32;
+                // If we were to have as many cells as the previous inner row then the
+                // cells must be this wide.
+                // Tells us how wide each cell would be in this row if we don’t subdivide
+                // Our ideal cell width is the same as the height of the row
+                let estimated_cell_width = circumference / previous_row_cell_count as f32;
 
-            // How many ideal sized cells fit into this new row
-            // Rounded up or down (1 or 2 - maybe more for row 1)
-            // We subdivide if the ratio is 2+
-            let ratio = (estimated_cell_width / row_height).round();
+                // How many ideal sized cells fit into this new row
+                // Rounded up or down (1 or 2 - maybe more for row 1)
+                // We subdivide if the ratio is 2+
+                let ratio = cmp::max(1, (estimated_cell_width / row_height).round() as usize);
 
-            let num_cells = previous_row_cell_count * ratio as usize;
+                let num_cells = previous_row_cell_count * ratio;
 
-            cell_counts[y] = num_cells;
+                cell_counts.push(num_cells);
+            }
         }
 
-        let per_row_cumulative_node_count = cell_counts
-            .iter()
-            .scan(0, |accumulator: &mut usize, cells_in_row: &usize| {
-                *accumulator += cells_in_row;
-                Some(*accumulator)
-            })
+        let per_row_cumulative_node_count = std::iter::once(0)
+            .chain(
+                cell_counts
+                    .iter()
+                    .scan(0, |accumulator: &mut usize, cells_in_row: &usize| {
+                        *accumulator += cells_in_row;
+                        Some(*accumulator)
+                    }),
+            )
             .map(NodesCount)
             .collect();
 
-        let size = cell_counts.iter().cloned().reduce(|x, y| x + y).unwrap_or(0);
+        let size = cell_counts.iter().sum();
 
         PolarGridDimensions {
             row_cell_counts: cell_counts,
@@ -143,9 +149,9 @@ impl GridDimensions for PolarGridDimensions {
 
     #[inline]
     fn columns(&self) -> ColumnsCount {
-        // There is no 'column' on a polar grid going all the way through from the
-        // outer row to the inner centre.
-        ColumnsCount(1)
+        // There is no straight 'column' on a polar grid, so a column is the line of cells from
+        // a cell on the outermost row inwards to the centre.
+        ColumnsCount(self.row_cell_counts.last().cloned().unwrap_or(0))
     }
 
     #[inline]
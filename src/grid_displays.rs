@@ -1,5 +1,5 @@
 use crate::{
-    cells::{Cartesian2DCoordinate, Cell, CompassPrimary, SquareCell},
+    cells::{Cartesian2DCoordinate, Cell, CompassHex, CompassPrimary, HexCell, SquareCell},
     grid::{Grid, IndexType},
     grid_traits::{GridDisplay, GridIterators},
     pathing::{Distances, MaxDistance},
@@ -75,7 +75,7 @@ impl<CellT: Cell> StartEndPointsDisplay<CellT> {
 }
 impl<CellT: Cell> GridDisplay<CellT> for StartEndPointsDisplay<CellT> {
     fn render_cell_body(&self, coord: CellT::Coord) -> String {
-        let contains_coordinate = |coordinates: &CellT::CoordinateSmallVec| coordinates.iter().any(|&c| c == coord);
+        let contains_coordinate = |coordinates: &CellT::CoordinateSmallVec| coordinates.contains(&coord);
 
         if contains_coordinate(&self.start_coordinates) {
             String::from(" S ")
@@ -87,7 +87,6 @@ impl<CellT: Cell> GridDisplay<CellT> for StartEndPointsDisplay<CellT> {
     }
 }
 
-// Todo - displaying other grid types, e.g. impl<GridIndexType: IndexType> fmt::Display for Grid<GridIndexType, HexCell>
 impl<GridIndexType, Iters> fmt::Display for Grid<GridIndexType, SquareCell, Iters>
 where
     GridIndexType: IndexType,
@@ -203,10 +202,10 @@ where
                     (false, false) => {
                         let access_se_from_east = self
                             .neighbour_at_direction(cell_coord, CompassPrimary::East)
-                            .map_or(false, |c| self.is_neighbour_linked(c, CompassPrimary::South));
+                            .is_some_and(|c| self.is_neighbour_linked(c, CompassPrimary::South));
                         let access_se_from_south = self
                             .neighbour_at_direction(cell_coord, CompassPrimary::South)
-                            .map_or(false, |c| self.is_neighbour_linked(c, CompassPrimary::East));
+                            .is_some_and(|c| self.is_neighbour_linked(c, CompassPrimary::East));
                         let show_right_section = !access_se_from_east;
                         let show_down_section = !access_se_from_south;
                         let show_up_section = !east_open;
@@ -250,3 +249,68 @@ where
         write!(f, "{}", output)
     }
 }
+
+impl<GridIndexType, Iters> fmt::Display for Grid<GridIndexType, HexCell, Iters>
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<HexCell>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Each flat topped hexagon is drawn as:
+        //  ___
+        // /   \
+        // \___/
+        // Neighbouring columns share their diagonal walls, so a column is 4 characters wide and odd columns are
+        // shifted down by one line. Walls are drawn wherever a cell is not linked to its neighbour in that direction.
+        const COLUMN_WIDTH: usize = 4;
+        let ColumnsCount(columns_count) = self.columns();
+        let RowsCount(rows_count) = self.rows();
+        if columns_count == 0 || rows_count == 0 {
+            return Ok(());
+        }
+
+        let canvas_width = COLUMN_WIDTH * columns_count + 1;
+        let canvas_height = 2 * rows_count + if columns_count > 1 { 2 } else { 1 };
+        let mut canvas = vec![vec![' '; canvas_width]; canvas_height];
+
+        for cell_coord in self.iter() {
+            let x0 = COLUMN_WIDTH * cell_coord.x as usize;
+            let y0 = 2 * cell_coord.y as usize + (cell_coord.x % 2) as usize;
+            let is_wall = |direction| !self.is_neighbour_linked(cell_coord, direction);
+
+            if is_wall(CompassHex::North) {
+                canvas[y0][x0 + 1..x0 + 4].copy_from_slice(&['_'; 3]);
+            }
+            if is_wall(CompassHex::South) {
+                canvas[y0 + 2][x0 + 1..x0 + 4].copy_from_slice(&['_'; 3]);
+            }
+            if is_wall(CompassHex::NorthWest) {
+                canvas[y0 + 1][x0] = '/';
+            }
+            if is_wall(CompassHex::SouthWest) {
+                canvas[y0 + 2][x0] = '\\';
+            }
+            if is_wall(CompassHex::NorthEast) {
+                canvas[y0 + 1][x0 + 4] = '\\';
+            }
+            if is_wall(CompassHex::SouthEast) {
+                canvas[y0 + 2][x0 + 4] = '/';
+            }
+
+            if let Some(ref displayer) = *self.grid_display() {
+                for (i, glyph) in displayer.render_cell_body(cell_coord).chars().take(3).enumerate() {
+                    canvas[y0 + 1][x0 + 1 + i] = glyph;
+                }
+            }
+        }
+
+        let mut output = String::with_capacity(canvas_height * (canvas_width + 1));
+        for line in &canvas {
+            let text: String = line.iter().collect();
+            output.push_str(text.trim_end());
+            output.push('\n');
+        }
+
+        write!(f, "{}", output)
+    }
+}
@@ -166,52 +166,84 @@ impl<CellT: Cell> GridIterators<CellT> for PolarGridIterators {
     }
 }
 
-#[allow(dead_code)] // TODO: WIP, actually used in grid iterators
-#[derive(Debug, Copy, Clone)]
+#[derive(Clone)]
 pub struct PolarBatchIter<CellT> {
     iter_type: BatchIterType,
-    iter_initial_length: usize,
+    batches_count: usize,
     current_index: usize,
-    row_length: RowLength,
-    rows_size: RowsCount,
-    col_length: ColumnLength,
-    cols_size: ColumnsCount,
+    dimensions: Rc<dyn GridDimensions>,
     cell_type: PhantomData<CellT>,
 }
 
 impl<CellT> PolarBatchIter<CellT> {
     fn new(iter_type: BatchIterType, dimensions: &Rc<dyn GridDimensions>) -> PolarBatchIter<CellT> {
-        let rows_size = dimensions.rows();
-        let cols_size = dimensions.columns();
+        let batches_count = if let BatchIterType::Row = iter_type {
+            dimensions.rows().0
+        } else {
+            dimensions.columns().0
+        };
         PolarBatchIter {
             iter_type,
-            iter_initial_length: rows_size.0 * cols_size.0,
+            batches_count,
             current_index: 0,
-            row_length: dimensions.row_length(None).unwrap(),
-            rows_size,
-            col_length: dimensions.column_length(None),
-            cols_size,
+            dimensions: dimensions.clone(),
             cell_type: PhantomData,
         }
     }
+
+    #[inline]
+    fn row_length(&self, row: usize) -> usize {
+        let RowLength(length) = self
+            .dimensions
+            .row_length(Some(RowIndex(row)))
+            .expect("invalid row index");
+        length
+    }
+}
+
+impl<CellT: Cell> fmt::Debug for PolarBatchIter<CellT> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PolarBatchIter :: iter_type: {:?}, current_index: {:?}, batches_count: {:?}",
+            self.iter_type, self.current_index, self.batches_count
+        )
+    }
 }
 
 impl<CellT: Cell> ExactSizeIterator for PolarBatchIter<CellT> {} // default impl using size_hint()
 impl<CellT: Cell> Iterator for PolarBatchIter<CellT> {
     type Item = Vec<CellT::Coord>;
     fn next(&mut self) -> Option<Self::Item> {
-        // this will be really slow for by column, row is fine
-        // start at the outside of the circle and work into the centre to define a "column"
+        if self.current_index >= self.batches_count {
+            return None;
+        }
 
-        if let BatchIterType::Row = self.iter_type {
-            None
+        let coords = if let BatchIterType::Row = self.iter_type {
+            let row = self.current_index;
+            (0..self.row_length(row))
+                .map(|i: usize| CellT::Coord::from_row_column_indices(ColumnIndex(i), RowIndex(row)))
+                .collect()
         } else {
-            None
-        }
+            // Start at the outside of the circle and work into the centre to define a "column".
+            // Each row inwards has the same or fewer cells, with each inward cell covering `ratio` outer cells.
+            let RowsCount(rows) = self.dimensions.rows();
+            let mut x = self.current_index;
+            let mut column = Vec::with_capacity(rows);
+            for row in (0..rows).rev() {
+                column.push(CellT::Coord::from_row_column_indices(ColumnIndex(x), RowIndex(row)));
+                if row > 0 {
+                    x = x * self.row_length(row - 1) / self.row_length(row);
+                }
+            }
+            column
+        };
+        self.current_index += 1;
+        Some(coords)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let lower_bound = self.iter_initial_length - self.current_index;
+        let lower_bound = self.batches_count - self.current_index;
         let upper_bound = lower_bound;
         (lower_bound, Some(upper_bound))
     }
@@ -13,6 +13,7 @@ pub trait GridDimensions {
     fn columns(&self) -> ColumnsCount;
     fn column_length(&self, column_index: Option<ColumnIndex>) -> ColumnLength;
     fn graph_size(&self) -> (NodesCount, EdgesCount);
+    /// The number of nodes in all the rows before `row_index`.
     fn nodes_count_up_to(&self, row_index: RowIndex) -> Option<NodesCount>;
 }
 
@@ -20,11 +21,17 @@ pub trait GridCoordinates<CellT: Cell> {
     fn grid_coordinate_to_index(&self, coord: CellT::Coord, dimensions: &Rc<dyn GridDimensions>) -> Option<usize>;
     fn is_valid_coordinate(&self, coord: CellT::Coord, dimensions: &Rc<dyn GridDimensions>) -> bool {
         let grid_2d_coord = coord.as_cartesian_2d();
-        let RowLength(width) = dimensions
-            .row_length(Some(RowIndex(grid_2d_coord.y as usize)))
-            .expect("RowIndex invalid");
         let ColumnLength(height) = dimensions.column_length(Some(ColumnIndex(grid_2d_coord.x as usize)));
-        (grid_2d_coord.x as usize) < width && (grid_2d_coord.y as usize) < height
+        if (grid_2d_coord.y as usize) < height {
+            // Rows may vary in length, e.g. on a polar grid, and there is no row length beyond the last row.
+            if let Some(RowLength(width)) = dimensions.row_length(Some(RowIndex(grid_2d_coord.y as usize))) {
+                (grid_2d_coord.x as usize) < width
+            } else {
+                false
+            }
+        } else {
+            false
+        }
     }
     fn random_cell(&self, rng: &mut SmallRng, dimensions: &Rc<dyn GridDimensions>) -> CellT::Coord; // consider &Rng simple trait object. Note <R : Rng> meant GridCoordinates could not be made a trait object
 }
@@ -37,6 +44,14 @@ pub trait GridIterators<CellT: Cell> {
     fn iter_column(&self, dimensions: &Rc<dyn GridDimensions>) -> Self::BatchIter;
 }
 
+/// Which cells of a grid are next to each other and which are linked, for cell types whose neighbours depend on the
+/// passages already carved, see `Cell::neighbours`.
+pub trait GridLinks<CellT: Cell> {
+    fn dimensions(&self) -> &dyn GridDimensions;
+    fn neighbour_at_direction(&self, coord: CellT::Coord, direction: CellT::Direction) -> Option<CellT::Coord>;
+    fn is_linked(&self, a: CellT::Coord, b: CellT::Coord) -> bool;
+}
+
 pub trait GridDisplay<CellT: Cell> {
     /// Render the contents of a grid cell as text.
     /// The String should be 3 glyphs long, padded if required.
@@ -1,45 +1,82 @@
 use crate::{
-    cells::SquareCell,
-    grid::Grid,
-    grid_coordinates::RectGridCoordinates,
-    grid_dimensions::RectGridDimensions,
-    grid_iterators::RectGridIterators,
-    units::{ColumnLength, RowLength},
+    cells::{Cell, HexCell, PolarCell, SquareCell, TriangleCell, WeaveCell},
+    grid::{Grid, IndexType},
+    grid_coordinates::{PolarGridCoordinates, RectGridCoordinates},
+    grid_dimensions::{PolarGridDimensions, RectGridDimensions},
+    grid_iterators::{PolarGridIterators, RectGridIterators},
+    grid_traits::GridDimensions,
+    units::{ColumnLength, RowLength, RowsCount},
 };
 use std::rc::Rc;
-use std::{u16, u32, u8};
 
 pub type SmallRectangularGrid = Grid<u8, SquareCell, RectGridIterators>;
 pub type MediumRectangularGrid = Grid<u16, SquareCell, RectGridIterators>;
 pub type LargeRectangularGrid = Grid<u32, SquareCell, RectGridIterators>;
+pub type PolarGrid = Grid<u32, PolarCell, PolarGridIterators>;
+pub type HexGrid = Grid<u32, HexCell, RectGridIterators>;
+pub type TriangleGrid = Grid<u32, TriangleCell, RectGridIterators>;
+pub type WeaveGrid = Grid<u32, WeaveCell, RectGridIterators>;
 
+/// A square celled grid of at most 255 cells.
 pub fn small_rect_grid(row_width: RowLength, column_height: ColumnLength) -> Option<SmallRectangularGrid> {
-    if row_width.0 * column_height.0 <= u8::MAX as usize {
-        Some(SmallRectangularGrid::new(
-            Rc::new(RectGridDimensions::new(row_width, column_height)),
-            Box::new(RectGridCoordinates),
-            RectGridIterators,
+    rect_layout_grid(row_width, column_height)
+}
+
+/// A square celled grid of at most 65,535 cells.
+pub fn medium_rect_grid(row_width: RowLength, column_height: ColumnLength) -> Option<MediumRectangularGrid> {
+    rect_layout_grid(row_width, column_height)
+}
+
+/// A square celled grid of at most 4,294,967,295 cells.
+pub fn large_rect_grid(row_width: RowLength, column_height: ColumnLength) -> Option<LargeRectangularGrid> {
+    rect_layout_grid(row_width, column_height)
+}
+
+/// A grid of rings around a centre cell, the rings further out split into more cells.
+pub fn polar_grid(rows: RowsCount) -> Option<PolarGrid> {
+    let dimensions = PolarGridDimensions::new(rows);
+    if dimensions.size().0 <= u32::MAX as usize {
+        Some(PolarGrid::new(
+            Rc::new(dimensions),
+            Box::new(PolarGridCoordinates),
+            PolarGridIterators,
         ))
     } else {
         None
     }
 }
 
-pub fn medium_rect_grid(row_width: RowLength, column_height: ColumnLength) -> Option<MediumRectangularGrid> {
-    if row_width.0 * column_height.0 <= u16::MAX as usize {
-        Some(MediumRectangularGrid::new(
-            Rc::new(RectGridDimensions::new(row_width, column_height)),
-            Box::new(RectGridCoordinates),
-            RectGridIterators,
-        ))
+/// A grid of hexagons, each column offset half a cell from its neighbours.
+pub fn hex_grid(row_width: RowLength, column_height: ColumnLength) -> Option<HexGrid> {
+    rect_layout_grid(row_width, column_height)
+}
+
+/// A grid of alternately upright and upside down triangles. A single column of triangles only joins them in pairs, so
+/// rows must be at least two cells wide.
+pub fn triangle_grid(row_width: RowLength, column_height: ColumnLength) -> Option<TriangleGrid> {
+    if row_width.0 >= 2 {
+        rect_layout_grid(row_width, column_height)
     } else {
         None
     }
 }
 
-pub fn large_rect_grid(row_width: RowLength, column_height: ColumnLength) -> Option<LargeRectangularGrid> {
-    if row_width.0 * column_height.0 <= u32::MAX as usize {
-        Some(LargeRectangularGrid::new(
+/// A square celled grid whose passages can tunnel under a corridor to the cell beyond.
+pub fn weave_grid(row_width: RowLength, column_height: ColumnLength) -> Option<WeaveGrid> {
+    rect_layout_grid(row_width, column_height)
+}
+
+/// A grid of cells laid out in rows and columns, `None` if there are more cells than the index type can count.
+fn rect_layout_grid<GridIndexType, CellT>(
+    row_width: RowLength,
+    column_height: ColumnLength,
+) -> Option<Grid<GridIndexType, CellT, RectGridIterators>>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+{
+    if row_width.0 * column_height.0 <= <GridIndexType as IndexType>::max().index() {
+        Some(Grid::new(
             Rc::new(RectGridDimensions::new(row_width, column_height)),
             Box::new(RectGridCoordinates),
             RectGridIterators,
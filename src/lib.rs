@@ -1,4 +1,7 @@
 //! **mazes** is a maze generation, visualisation and route finding library.
+//!
+//! Showing mazes in a window needs SDL, which is the default `sdl` feature. Without it everything else, including
+//! rendering images, builds with no SDL libraries.
 
 // TODO infrastructure:
 // - public docs / tutorial / examples
@@ -14,7 +17,11 @@ pub mod grid_traits;
 pub mod grids;
 pub mod masks;
 pub mod pathing;
+pub mod play;
 pub mod renderers;
+#[cfg(feature = "sdl")]
 mod sdl;
+pub mod serialization;
+pub mod stats;
 pub mod units;
 mod utils;
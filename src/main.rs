@@ -1,6 +1,7 @@
 #![windows_subsystem = "windows"] // Do not display a console when running a windowed application
 
 use docopt::Docopt;
+use error_chain::bail;
 use mazes::{
     cells::{Cartesian2DCoordinate, Cell, SquareCell},
     generators,
@@ -11,17 +12,25 @@ use mazes::{
     grid_iterators::RectGridIterators,
     grid_traits::GridDisplay,
     masks::BinaryMask2D,
-    pathing, renderers, units,
+    pathing, renderers, serialization, stats, units,
 };
+use rand::{rngs::SmallRng, SeedableRng};
 use serde_derive::Deserialize;
-use std::{fs::File, io, io::prelude::*, path::Path, rc::Rc};
+use std::{
+    fs::{self, File},
+    io,
+    io::prelude::*,
+    path::Path,
+    rc::Rc,
+};
 
 const USAGE: &str = "Mazes
 
 Usage:
     mazes_driver -h | --help
-    mazes_driver [(--grid-size=<n>|[--grid-width=<w> --grid-height=<h>])] [--block-passages=<n>] [--save-edges=<path>]
-    mazes_driver render (binary|sidewinder|aldous-broder|wilson|hunt-kill|recursive-backtracker) [text --text-out=<path> (--show-distances|--show-path) (--furthest-end-point --start-point-x=<x> --start-point-y=<y>|--end-point-x=<e1> --end-point-y=<e2> --start-point-x=<x> --start-point-y=<y>)] [image --image-out=<path> --cell-pixels=<n> --colour-distances --show-path --screen-view --mark-start-end ] [(--grid-size=<n>|[--grid-width=<w> --grid-height=<h>])] [--mask-file=<path>] [--block-passages=<n>] [--save-edges=<path>]
+    mazes_driver [(--grid-size=<n>|[--grid-width=<w> --grid-height=<h>])] [--load-edges=<path>|--load-json=<path>|--load-text=<path>|--load-image=<path>] [--image-cell-pixels=<n>] [--braid=<p>] [--block-passages=<n>] [--save-edges=<path>] [--save-json=<path>] [--save-dot=<path>] [--save-graphml=<path>] [--seed=<n>]
+    mazes_driver render (binary|sidewinder|ellers|aldous-broder|wilson|hunt-kill|recursive-backtracker|prims|true-prims|kruskal|growing-tree|recursive-division|--load-edges=<path>|--load-json=<path>|--load-text=<path>|--load-image=<path>) [text --text-out=<path> (--show-distances|--show-path) (--furthest-end-point --start-point-x=<x> --start-point-y=<y>|--end-point-x=<e1> --end-point-y=<e2> --start-point-x=<x> --start-point-y=<y>)] [image --image-out=<path> --format=<f> --cell-pixels=<n> --colour-distances --show-path --screen-view --mark-start-end --play --animate=<path> --frames-per-step=<n> --hold-frames=<n>] [(--grid-size=<n>|[--grid-width=<w> --grid-height=<h>])] [--mask-file=<path>] [--image-cell-pixels=<n>] [--braid=<p>] [--block-passages=<n>] [--save-edges=<path>] [--save-json=<path>] [--save-dot=<path>] [--save-graphml=<path>] [--seed=<n>] [--newest-chance=<p>] [--room-size=<n>]
+    mazes_driver stats (binary|sidewinder|ellers|aldous-broder|wilson|hunt-kill|recursive-backtracker|prims|true-prims|kruskal|growing-tree|recursive-division|--load-edges=<path>|--load-json=<path>|--load-text=<path>|--load-image=<path>) [--json] [(--grid-size=<n>|[--grid-width=<w> --grid-height=<h>])] [--mask-file=<path>] [--image-cell-pixels=<n>] [--braid=<p>] [--block-passages=<n>] [--seed=<n>] [--newest-chance=<p>] [--room-size=<n>]
 
 Options:
     -h --help              Show this screen.
@@ -36,14 +45,32 @@ Options:
     --start-point-y=<y>    y coordinate of the path start
     --end-point-x=<e1>     x coordinate of the path end
     --end-point-y=<e2>     y coordinate of the path end
-     <path>     Output file path for an image rendering of a maze. Always PNG format.
+    --image-out=<path>     Output file path for an image rendering of a maze.
+    --format=<f>           Image file format, png or svg. SVG scales to any size without blurring [default: png].
     --cell-pixels=<n>      Pixel count to render one cell wall in a maze [default: 10] max 255.
     --colour-distances     Indicate the distance from a starting point to any cell by the cell's background colour.
     --screen-view          When rendering to an image and saving to a file, also show the image on the screen.
     --mark-start-end       Draw an 'S' (start) and 'E' (end) to show the path start and end points.
+    --play                 Play the maze on screen, moving from the start to the end point with the arrow keys or WASD. R restarts, Q quits.
+    --animate=<path>       Output file path for an animation of the maze being generated. A .gif is an animated GIF, a .png is a numbered sequence of PNG frames.
+    --frames-per-step=<n>  Animation frames to show each generator step for [default: 1].
+    --hold-frames=<n>      Animation frames to show the finished maze for [default: 25].
     --mask-file=<path>     Path to a mask data image file (e.g. grayscale), where each pixel acts as a grid cell mask or not depending upon its intensity.
+    --braid=<p>            Remove dead ends with probability p, 0.0 to 1.0, creating loops so there are multiple routes.
     --block-passages=<n>   Randomly choose n cells to block a passage from.
+    --newest-chance=<p>    The growing tree chance, 0.0 to 1.0, of growing from the newest cell instead of a random one [default: 0.75].
+    --room-size=<n>        Recursive division regions no more than n cells wide and high may be left as open rooms [default: 0].
+    --seed=<n>             Seed for the random number generator, the same seed and options regenerate the same maze. Random if not given.
+    --json                 Output the maze statistics as JSON.
+    --load-edges=<path>    Load a maze saved by --save-edges instead of generating one. The grid size must match the saved maze.
     --save-edges=<path>    Serialize the maze to a text file: each line is a pair of numbers. Line 1: n(#vertices) m(#edges). Line 2+ edge between vertices. Uses 1-based vertex indices.
+    --load-json=<path>     Load a maze saved by --save-json instead of generating one. The grid size comes from the saved maze.
+    --load-text=<path>     Load a maze drawn as text, like the text render or with ASCII +---+ and | walls, instead of generating one.
+    --load-image=<path>    Load a maze from an image of it, e.g. a PNG rendered by this program, instead of generating one.
+    --image-cell-pixels=<n>  Pixel size of a cell in the --load-image maze, worked out from the walls of rendered PNGs if not given.
+    --save-json=<path>     Save the maze as JSON: the grid's cell type, dimensions, links and weights.
+    --save-dot=<path>      Save the maze graph in the Graphviz DOT format, with cell positions. Cells are labelled with their distance from the start point if one is given.
+    --save-graphml=<path>  Save the maze graph in the GraphML format, with cell positions. Cells have their distance from the start point if one is given.
 ";
 #[derive(Debug, Deserialize)]
 struct MazeArgs {
@@ -51,21 +78,34 @@ struct MazeArgs {
     flag_grid_width: usize,
     flag_grid_height: usize,
     cmd_render: bool,
+    cmd_stats: bool,
+    flag_json: bool,
     cmd_binary: bool,
     cmd_sidewinder: bool,
+    cmd_ellers: bool,
     cmd_aldous_broder: bool,
     cmd_wilson: bool,
     cmd_hunt_kill: bool,
     cmd_recursive_backtracker: bool,
+    cmd_prims: bool,
+    cmd_true_prims: bool,
+    cmd_kruskal: bool,
+    cmd_growing_tree: bool,
+    cmd_recursive_division: bool,
     cmd_text: bool,
     flag_text_out: String,
     cmd_image: bool,
     flag_image_out: String,
+    flag_format: String,
     flag_cell_pixels: u8,
     flag_screen_view: bool,
     flag_colour_distances: bool,
     flag_show_distances: bool,
     flag_mark_start_end: bool,
+    flag_play: bool,
+    flag_animate: String,
+    flag_frames_per_step: u32,
+    flag_hold_frames: u32,
     flag_show_path: bool,
     flag_furthest_end_point: bool,
     flag_start_point_x: Option<u32>,
@@ -73,13 +113,26 @@ struct MazeArgs {
     flag_end_point_x: Option<u32>,
     flag_end_point_y: Option<u32>,
     flag_mask_file: String,
+    flag_braid: Option<f64>,
     flag_block_passages: Option<usize>,
     flag_save_edges: String,
+    flag_load_edges: String,
+    flag_save_json: String,
+    flag_load_json: String,
+    flag_load_text: String,
+    flag_load_image: String,
+    flag_image_cell_pixels: Option<u32>,
+    flag_save_dot: String,
+    flag_save_graphml: String,
+    flag_seed: Option<u64>,
+    flag_newest_chance: f64,
+    flag_room_size: usize,
 }
 
 // We'll put our errors in an `errors` module, and other modules in
 // this crate will `use errors::*;` to get access to everything
 // `error_chain!` creates.
+#[allow(unexpected_cfgs)] // error_chain checks for cfgs that are unknown to the compiler
 mod errors {
     // Create the Error, ErrorKind, ResultExt, and Result types
     // Result is a typedef of std `Result` with the error type our own `Error`
@@ -99,8 +152,34 @@ use crate::errors::*;
 fn main() -> Result<()> {
     let args: MazeArgs = Docopt::new(USAGE).and_then(|d| d.deserialize())?;
 
+    let loaded_grid = if !args.flag_load_json.is_empty() {
+        let json = fs::read_to_string(&args.flag_load_json)
+            .chain_err(|| format!("Failed to read maze from JSON file {}", args.flag_load_json))?;
+        Some(
+            serde_json::from_str::<Grid<u32, SquareCell, RectGridIterators>>(&json)
+                .chain_err(|| format!("Failed to load maze from JSON file {}", args.flag_load_json))?,
+        )
+    } else if !args.flag_load_text.is_empty() {
+        let text = fs::read_to_string(&args.flag_load_text)
+            .chain_err(|| format!("Failed to read maze from text file {}", args.flag_load_text))?;
+        Some(
+            serialization::read_text_maze(&text)
+                .chain_err(|| format!("Failed to load maze from text file {}", args.flag_load_text))?,
+        )
+    } else if !args.flag_load_image.is_empty() {
+        let image = image::open(Path::new(&args.flag_load_image))?;
+        Some(
+            serialization::read_image_maze(&image, args.flag_image_cell_pixels)
+                .chain_err(|| format!("Failed to load maze from image file {}", args.flag_load_image))?,
+        )
+    } else {
+        None
+    };
+
     let large_grid_cell_count = 25 * 25;
-    let (width, height) = if let Some(square_grid_size) = args.flag_grid_size {
+    let (width, height) = if let Some(ref grid) = loaded_grid {
+        (grid.row_length().map_or(0, |length| length.0), grid.column_length().0)
+    } else if let Some(square_grid_size) = args.flag_grid_size {
         (square_grid_size, square_grid_size)
     } else {
         (args.flag_grid_width, args.flag_grid_height)
@@ -114,14 +193,27 @@ fn main() -> Result<()> {
     let do_text_render =
         args.cmd_render && (args.cmd_text || (!any_render_option && grid_size < large_grid_cell_count));
 
-    let mut maze_grid = Grid::<u32, SquareCell, RectGridIterators>::new(
-        Rc::new(RectGridDimensions::new(
-            units::RowLength(width),
-            units::ColumnLength(height),
-        )),
-        Box::new(RectGridCoordinates),
-        RectGridIterators,
-    );
+    if let Some(braid_chance) = args.flag_braid {
+        if !(0.0..=1.0).contains(&braid_chance) {
+            bail!(
+                "The --braid probability must be between 0.0 and 1.0, got {}.",
+                braid_chance
+            );
+        }
+    }
+    if !(0.0..=1.0).contains(&args.flag_newest_chance) {
+        bail!(
+            "The --newest-chance probability must be between 0.0 and 1.0, got {}.",
+            args.flag_newest_chance
+        );
+    }
+    let is_animated = !args.flag_animate.is_empty();
+    let is_loaded = !args.flag_load_edges.is_empty() || loaded_grid.is_some();
+    if is_animated && is_loaded {
+        bail!("A loaded maze cannot be animated, --animate needs a maze generator.");
+    }
+
+    let mut maze_grid = loaded_grid.unwrap_or_else(|| new_maze_grid(width, height));
 
     let mask = if !args.flag_mask_file.is_empty() {
         Some(load_binary_mask(&args.flag_mask_file)?)
@@ -129,14 +221,86 @@ fn main() -> Result<()> {
         None
     };
 
-    generate_maze_on_grid(&mut maze_grid, &args, mask.as_ref());
+    // A random seed is reported on stderr so that the maze can be regenerated, keeping stdout for the maze itself.
+    let seed = args.flag_seed.unwrap_or_else(|| {
+        let seed = rand::random();
+        eprintln!("Seed: {}", seed);
+        seed
+    });
+    let mut rng = SmallRng::seed_from_u64(seed);
+
+    let generation_steps = if is_loaded {
+        if !args.flag_load_edges.is_empty() {
+            let edges = fs::read_to_string(&args.flag_load_edges)
+                .chain_err(|| format!("Failed to read maze graph from text file {}", args.flag_load_edges))?;
+            serialization::read_edges(&mut maze_grid, &edges)
+                .chain_err(|| format!("Failed to load maze graph from text file {}", args.flag_load_edges))?;
+        }
+        None
+    } else if is_animated {
+        let mut steps = vec![];
+        generate_maze_on_grid(
+            generators::ObservedGrid::new(&mut maze_grid, &mut |step| steps.push(step)),
+            &args,
+            mask.as_ref(),
+            &mut rng,
+        );
+        Some(steps)
+    } else {
+        generate_maze_on_grid(
+            generators::ObservedGrid::new(&mut maze_grid, &mut |_| {}),
+            &args,
+            mask.as_ref(),
+            &mut rng,
+        );
+        None
+    };
 
-    if let Some(wall_count) = args.flag_block_passages {
-        generators::rebuild_random_walls(&mut maze_grid, wall_count);
+    if !args.flag_save_edges.is_empty() {
+        write_text_to_file(&serialization::write_edges(&maze_grid), &args.flag_save_edges)
+            .chain_err(|| format!("Failed to write maze graph to text file {}", args.flag_save_edges))?;
+    }
+    if !args.flag_save_json.is_empty() {
+        let json = serde_json::to_string_pretty(&maze_grid).chain_err(|| "Failed to serialize the maze to JSON")?;
+        write_text_to_file(&json, &args.flag_save_json)
+            .chain_err(|| format!("Failed to write maze to JSON file {}", args.flag_save_json))?;
+    }
+    if !args.flag_save_dot.is_empty() || !args.flag_save_graphml.is_empty() {
+        let distances = if let (Some(start_x), Some(start_y)) = (args.flag_start_point_x, args.flag_start_point_y) {
+            Some(
+                pathing::Distances::<SquareCell, u32>::for_grid(
+                    &maze_grid,
+                    Cartesian2DCoordinate::new(start_x, start_y),
+                )
+                .ok_or("Provided invalid start coordinate from which to label path distances.")?,
+            )
+        } else {
+            None
+        };
+        if !args.flag_save_dot.is_empty() {
+            write_text_to_file(
+                &serialization::write_dot(&maze_grid, distances.as_ref()),
+                &args.flag_save_dot,
+            )
+            .chain_err(|| format!("Failed to write maze graph to DOT file {}", args.flag_save_dot))?;
+        }
+        if !args.flag_save_graphml.is_empty() {
+            write_text_to_file(
+                &serialization::write_graphml(&maze_grid, distances.as_ref()),
+                &args.flag_save_graphml,
+            )
+            .chain_err(|| format!("Failed to write maze graph to GraphML file {}", args.flag_save_graphml))?;
+        }
     }
 
-    if !args.flag_save_edges.is_empty() {
-        save_maze_graph(&maze_grid, &args.flag_save_edges)?;
+    if args.cmd_stats {
+        let maze_stats = stats::maze_stats(&maze_grid, mask.as_ref());
+        if args.flag_json {
+            println!("{}", maze_stats.to_json());
+        } else {
+            println!("{}", maze_stats);
+        }
+        return Ok(());
     }
 
     let longest_path = longest_path_from_arg_constraints(&args, &maze_grid, mask.as_ref())?;
@@ -154,6 +318,14 @@ fn main() -> Result<()> {
 
     if do_image_render {
         let is_image_path_set = !args.flag_image_out.is_empty();
+        let is_svg = match args.flag_format.as_str() {
+            "png" => false,
+            "svg" => true,
+            other => bail!("Unknown image format '{}', expected png or svg.", other),
+        };
+        if is_svg && !is_image_path_set {
+            bail!("An SVG image needs an --image-out file path.");
+        }
         let out_image_path = if is_image_path_set {
             Some(Path::new(&args.flag_image_out))
         } else {
@@ -186,45 +358,110 @@ fn main() -> Result<()> {
         } else {
             None
         };
+        let show_on_screen = args.flag_screen_view || (!is_image_path_set && !is_animated && !args.flag_play);
+        if !cfg!(feature = "sdl") && (show_on_screen || args.flag_play) {
+            bail!("Showing the maze in a window needs the sdl feature, save it with --image-out instead.");
+        }
+
         let render_options = renderers::RenderOptionsBuilder::new()
-            .show_on_screen(args.flag_screen_view || !is_image_path_set)
+            .show_on_screen(show_on_screen)
             .colour_distances(args.flag_colour_distances)
             .mark_start_end(args.flag_mark_start_end)
             .start(start_opt.map(Cartesian2DCoordinate::from))
             .end(end_opt.map(Cartesian2DCoordinate::from))
             .show_path(args.flag_show_path)
             .distances(distances.as_ref())
-            .output_file(out_image_path)
+            .output_file(if is_svg { None } else { out_image_path })
             .path(path_opt)
             .cell_side_pixels_length(args.flag_cell_pixels)
+            .frames_per_step(args.flag_frames_per_step)
+            .hold_frames(args.flag_hold_frames)
             .build();
-        renderers::render_square_grid(&maze_grid, &render_options);
+
+        if let Some(steps) = generation_steps {
+            renderers::render_square_grid_animation(
+                &mut new_maze_grid(width, height),
+                steps,
+                &render_options,
+                Path::new(&args.flag_animate),
+            )
+            .chain_err(|| format!("Failed to write maze animation {}", args.flag_animate))?;
+        }
+
+        if is_svg {
+            write_text_to_file(
+                &renderers::square_grid_svg(&maze_grid, &render_options),
+                &args.flag_image_out,
+            )
+            .chain_err(|| format!("Failed to write maze to svg file {}", args.flag_image_out))?;
+        }
+        if (!is_svg && (is_image_path_set || !is_animated)) || args.flag_screen_view {
+            renderers::render_square_grid(&maze_grid, &render_options);
+        }
+        #[cfg(feature = "sdl")]
+        if args.flag_play {
+            renderers::play_square_grid(&maze_grid, &render_options);
+        }
     }
 
     Ok(())
 }
 
+fn new_maze_grid(width: usize, height: usize) -> Grid<u32, SquareCell, RectGridIterators> {
+    Grid::<u32, SquareCell, RectGridIterators>::new(
+        Rc::new(RectGridDimensions::new(
+            units::RowLength(width),
+            units::ColumnLength(height),
+        )),
+        Box::new(RectGridCoordinates),
+        RectGridIterators,
+    )
+}
+
+/// Generate the maze chosen by the arguments, then braid it and block passages if asked to.
 fn generate_maze_on_grid(
-    maze_grid: &mut Grid<u32, SquareCell, RectGridIterators>,
+    mut maze_grid: generators::ObservedGrid<u32, SquareCell, RectGridIterators>,
     maze_args: &MazeArgs,
     mask: Option<&BinaryMask2D>,
+    rng: &mut SmallRng,
 ) {
-    if maze_args.cmd_render {
+    if maze_args.cmd_render || maze_args.cmd_stats {
         if maze_args.cmd_binary {
-            generators::binary_tree(maze_grid);
+            generators::binary_tree(maze_grid.reborrow(), rng);
         } else if maze_args.cmd_sidewinder {
-            generators::sidewinder(maze_grid);
+            generators::sidewinder(maze_grid.reborrow(), rng);
+        } else if maze_args.cmd_ellers {
+            generators::ellers(maze_grid.reborrow(), rng);
         } else if maze_args.cmd_aldous_broder {
-            generators::aldous_broder(maze_grid, mask);
+            generators::aldous_broder(maze_grid.reborrow(), mask, rng);
         } else if maze_args.cmd_wilson {
-            generators::wilson(maze_grid, mask);
+            generators::wilson(maze_grid.reborrow(), mask, rng);
         } else if maze_args.cmd_hunt_kill {
-            generators::hunt_and_kill(maze_grid, mask);
+            generators::hunt_and_kill(maze_grid.reborrow(), mask, rng);
         } else if maze_args.cmd_recursive_backtracker {
-            generators::recursive_backtracker(maze_grid, mask);
+            generators::recursive_backtracker(maze_grid.reborrow(), mask, rng);
+        } else if maze_args.cmd_prims {
+            generators::simplified_prims(maze_grid.reborrow(), mask, rng);
+        } else if maze_args.cmd_true_prims {
+            generators::true_prims(maze_grid.reborrow(), mask, rng);
+        } else if maze_args.cmd_kruskal {
+            generators::kruskal(maze_grid.reborrow(), mask, rng);
+        } else if maze_args.cmd_growing_tree {
+            let selection = generators::GrowingTreeSelection::NewestOrRandom(maze_args.flag_newest_chance);
+            generators::growing_tree(maze_grid.reborrow(), mask, selection, rng);
+        } else if maze_args.cmd_recursive_division {
+            generators::recursive_division(maze_grid.reborrow(), maze_args.flag_room_size, rng);
         }
     } else {
-        generators::sidewinder(maze_grid);
+        generators::sidewinder(maze_grid.reborrow(), rng);
+    }
+
+    if let Some(braid_chance) = maze_args.flag_braid {
+        generators::braid(maze_grid.reborrow(), braid_chance, rng);
+    }
+
+    if let Some(wall_count) = maze_args.flag_block_passages {
+        generators::rebuild_random_walls(maze_grid.reborrow(), wall_count, rng);
     }
 }
 
@@ -233,6 +470,7 @@ fn generate_maze_on_grid(
 /// - Start and End point markers if supplied else nothing
 /// - Distances from some start cell to all other cells
 /// - Shortest path between a start and end point
+///
 /// Default to finding the start and end point of the longest path in the maze if required to show a path
 /// or asked to find the point furthest away from a start point
 /// Use the start of the longest path if asked to show distances to all other cells but no start provided
@@ -367,6 +605,7 @@ fn maze_arg_requires_start_and_end_point(maze_args: &MazeArgs) -> bool {
         || maze_args.flag_show_path
         || maze_args.flag_colour_distances
         || maze_args.flag_mark_start_end
+        || maze_args.flag_play
 }
 
 fn as_coordinate_smallvec(coord: Cartesian2DCoordinate) -> <SquareCell as Cell>::CoordinateSmallVec {
@@ -377,7 +616,7 @@ fn as_coordinate_smallvec(coord: Cartesian2DCoordinate) -> <SquareCell as Cell>:
 }
 
 fn load_binary_mask(file_path_str: &str) -> Result<BinaryMask2D> {
-    let img = image::open(&Path::new(file_path_str))?;
+    let img = image::open(Path::new(file_path_str))?;
     Ok(BinaryMask2D::from_image(&img))
 }
 
@@ -386,34 +625,3 @@ fn write_text_to_file(data: &str, file_name: &str) -> io::Result<()> {
     f.write_all(data.as_bytes())?;
     Ok(())
 }
-
-fn save_maze_graph(maze_grid: &Grid<u32, SquareCell, RectGridIterators>, file_path: &str) -> Result<()> {
-    let mut graph_data = String::new();
-    let vertices_count = maze_grid.size();
-    let edges_count = maze_grid.links_count();
-    graph_data.push_str(vertices_count.to_string().as_ref());
-    graph_data.push(' ');
-    graph_data.push_str(edges_count.to_string().as_ref());
-    graph_data.push('\n');
-
-    for (src, dst) in maze_grid.iter_links() {
-        let index_a = maze_grid
-            .grid_coordinate_to_index(src)
-            .expect("Links iter should give valid coordinate");
-        let index_b = maze_grid
-            .grid_coordinate_to_index(dst)
-            .expect("Links iter should give valid coordinate");
-        let src_as_1_based_index = index_a + 1;
-        let dst_as_1_based_index = index_b + 1;
-
-        graph_data.push_str(src_as_1_based_index.to_string().as_ref());
-        graph_data.push(' ');
-        graph_data.push_str(dst_as_1_based_index.to_string().as_ref());
-        graph_data.push('\n');
-    }
-
-    write_text_to_file(&graph_data, file_path)
-        .chain_err(|| format!("Failed to write maze graph to text file {}", file_path))?;
-
-    Ok(())
-}
@@ -5,6 +5,8 @@ use crate::{
     units::{ColumnIndex, Height, RowIndex, Width},
 };
 use image::{DynamicImage, Luma};
+use serde::{de::Error as _, Deserializer, Serializer};
+use serde_derive::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct BinaryMask2D {
@@ -82,3 +84,45 @@ impl BinaryMask2D {
         }
     }
 }
+
+/// The saved form of a mask: its size and the row major indices of the masked out cells.
+#[derive(Serialize, Deserialize)]
+struct SavedMask {
+    width: u32,
+    height: u32,
+    masked: Vec<u32>,
+}
+
+impl serde::Serialize for BinaryMask2D {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let saved = SavedMask {
+            width: self.width,
+            height: self.height,
+            masked: self.mask.iter().map(|bit_index| bit_index as u32).collect(),
+        };
+        serde::Serialize::serialize(&saved, serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for BinaryMask2D {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved: SavedMask = serde::Deserialize::deserialize(deserializer)?;
+        let mask_size = u64::from(saved.width) * u64::from(saved.height);
+        let mut mask = BitSet::with_capacity(mask_size as usize);
+        for bit_index in saved.masked {
+            if u64::from(bit_index) >= mask_size {
+                return Err(D::Error::custom(format!(
+                    "masked cell {} is outside the {}x{} mask",
+                    bit_index, saved.width, saved.height
+                )));
+            }
+            mask.insert(bit_index as usize);
+        }
+
+        Ok(BinaryMask2D {
+            mask,
+            width: saved.width,
+            height: saved.height,
+        })
+    }
+}
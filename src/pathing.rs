@@ -25,7 +25,7 @@
 //   x requires heap allocating the graph, though that's much data - most of it is implemented as Vectors anyway.
 
 use crate::{
-    cells::{Cell, Coordinate},
+    cells::{Cartesian2DCoordinate, Cell, Coordinate},
     grid::{Grid, IndexType},
     grid_traits::GridIterators,
     masks::BinaryMask2D,
@@ -35,8 +35,12 @@ use crate::{
 };
 
 use num::traits::{Bounded, One, Unsigned, Zero};
+use serde::{de::Error as _, Deserializer, Serializer};
+use serde_derive::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::{
+    cmp,
+    collections::{BinaryHeap, VecDeque},
     fmt::{Debug, Display, LowerHex},
     marker::PhantomData,
     ops::Add,
@@ -152,6 +156,60 @@ where
     }
 }
 
+/// The saved form of distances: the start and each reached cell's distance, ordered by cell.
+/// The maximum distance is worked out again when loading.
+#[derive(Serialize, Deserialize)]
+struct SavedDistances<Coord, MaxDistanceT> {
+    start: Coord,
+    distances: Vec<(Coord, MaxDistanceT)>,
+}
+
+impl<CellT, MaxDistanceT> serde::Serialize for Distances<CellT, MaxDistanceT>
+where
+    CellT: Cell,
+    CellT::Coord: serde::Serialize,
+    MaxDistanceT: MaxDistance + serde::Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut distances = self.distances.iter().map(|(c, d)| (*c, *d)).collect::<Vec<_>>();
+        distances.sort_unstable();
+        let saved = SavedDistances {
+            start: self.start_coordinate,
+            distances,
+        };
+        serde::Serialize::serialize(&saved, serializer)
+    }
+}
+
+impl<'de, CellT, MaxDistanceT> serde::Deserialize<'de> for Distances<CellT, MaxDistanceT>
+where
+    CellT: Cell,
+    CellT::Coord: serde::Deserialize<'de>,
+    MaxDistanceT: MaxDistance + serde::Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved: SavedDistances<CellT::Coord, MaxDistanceT> = serde::Deserialize::deserialize(deserializer)?;
+        let mut distances = utils::fnv_hashmap(saved.distances.len());
+        let mut max = Zero::zero();
+        for (coord, distance) in saved.distances {
+            max = cmp::max(max, distance);
+            if distances.insert(coord, distance).is_some() {
+                return Err(D::Error::custom(format!("duplicate distance for cell {:?}", coord)));
+            }
+        }
+        if distances.get(&saved.start) != Some(&Zero::zero()) {
+            return Err(D::Error::custom("the start cell must be at distance 0"));
+        }
+
+        Ok(Distances {
+            start_coordinate: saved.start,
+            distances,
+            max_distance: max,
+            cell_type: PhantomData,
+        })
+    }
+}
+
 pub fn shortest_path<GridIndexType, MaxDistanceT, CellT, Iters>(
     grid: &Grid<GridIndexType, CellT, Iters>,
     distances_from_start: &Distances<CellT, MaxDistanceT>,
@@ -220,6 +278,112 @@ where
     Some(path)
 }
 
+/// Dijkstra's algorithm: the cheapest cost from the start to every reachable cell, using the grid's cell and link
+/// weights as the cost of each step (see `Grid::step_cost`).
+///
+/// `Distances::for_grid` counts every step as costing 1, which only gives the same distances while the grid has its
+/// default weights.
+/// Returns `None` if the start is not on the grid.
+pub fn dijkstra<GridIndexType, CellT, Iters>(
+    grid: &Grid<GridIndexType, CellT, Iters>,
+    start_coordinate: CellT::Coord,
+) -> Option<Distances<CellT, u32>>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    if !grid.is_valid_coordinate(start_coordinate) {
+        return None;
+    }
+
+    let mut distances: FnvHashMap<CellT::Coord, u32> = utils::fnv_hashmap(grid.size());
+    distances.insert(start_coordinate, 0);
+    let mut max_distance = 0;
+
+    // Min-heap of the cheapest known cost to each cell, which may hold outdated costs for cells later found cheaper.
+    let mut frontier = BinaryHeap::new();
+    frontier.push(cmp::Reverse((0, start_coordinate)));
+
+    while let Some(cmp::Reverse((cost_to_cell, cell_coord))) = frontier.pop() {
+        if distances.get(&cell_coord).is_some_and(|&best| best < cost_to_cell) {
+            continue;
+        }
+        max_distance = cmp::max(max_distance, cost_to_cell);
+
+        let links = grid
+            .links(cell_coord)
+            .expect("Source cell has an invalid cell coordinate.");
+        for &link_coordinate in &*links {
+            let step_cost = grid
+                .step_cost(cell_coord, link_coordinate)
+                .expect("Linked cells have a step cost.");
+            let cost_to_link = cost_to_cell.saturating_add(step_cost);
+            if distances
+                .get(&link_coordinate)
+                .map_or(true, |&best| cost_to_link < best)
+            {
+                distances.insert(link_coordinate, cost_to_link);
+                frontier.push(cmp::Reverse((cost_to_link, link_coordinate)));
+            }
+        }
+    }
+
+    Some(Distances {
+        start_coordinate,
+        distances,
+        max_distance,
+        cell_type: PhantomData,
+    })
+}
+
+/// The cheapest path from the start of the `dijkstra` distances to the end point, which may be longer than the
+/// shortest path when it goes around costly cells or links.
+pub fn cheapest_path<GridIndexType, CellT, Iters>(
+    grid: &Grid<GridIndexType, CellT, Iters>,
+    costs_from_start: &Distances<CellT, u32>,
+    end_point: CellT::Coord,
+) -> Option<Vec<CellT::Coord>>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    costs_from_start.distance_from_start_to(end_point)?;
+    let start = costs_from_start.start();
+
+    // Search back from the end for the start, only stepping back to cells that the cheapest route could have come
+    // from. Zero cost cells and links make several cells equally cheap, so the cells already searched are skipped
+    // rather than going around in circles between them.
+    let mut next_towards_end: FnvHashMap<CellT::Coord, CellT::Coord> = utils::fnv_hashmap(grid.size());
+    let mut to_search = VecDeque::from([end_point]);
+    while let Some(cell_coord) = to_search.pop_front() {
+        if cell_coord == start {
+            let mut path = vec![start];
+            while let Some(next_coord) = next_towards_end.get(path.last().expect("path is never empty")) {
+                path.push(*next_coord);
+            }
+            return Some(path);
+        }
+
+        let cell_cost = costs_from_start.distance_from_start_to(cell_coord)?;
+        for &link_coordinate in &*grid.links(cell_coord)? {
+            let is_on_cheapest_route = costs_from_start
+                .distance_from_start_to(link_coordinate)
+                .zip(grid.step_cost(link_coordinate, cell_coord))
+                .is_some_and(|(cost_to_link, step_cost)| cost_to_link.checked_add(step_cost) == Some(cell_cost));
+            if is_on_cheapest_route && link_coordinate != end_point && !next_towards_end.contains_key(&link_coordinate)
+            {
+                next_towards_end.insert(link_coordinate, cell_coord);
+                to_search.push_back(link_coordinate);
+            }
+        }
+    }
+
+    // The start is never reached if the distances do not match the grid's weights.
+    None
+}
+
 /// Works only as long as we are looking at a perfect maze, otherwise you get back some arbitrary path back.
 /// If the mask creates disconnected subgraphs it may not be the longest path.
 pub fn dijkstra_longest_path<GridIndexType, MaxDistanceT, CellT, Iters>(
@@ -253,16 +417,205 @@ where
     shortest_path(grid, &distances_from_start, end_point)
 }
 
+/// Estimates the number of steps between two cells for an A* search.
+///
+/// The search only finds shortest paths when the estimate never exceeds the real number of steps.
+/// The estimates work on `Coordinate::as_cartesian_2d`, and `astar` divides them by the estimate of the longest
+/// single step, see `Cell::link_offsets`, so they underestimate on any grid as long as an estimate is never more than
+/// the sum of the estimates of the steps along the way, as for a distance.
+pub trait Heuristic {
+    fn estimate(&self, from: Cartesian2DCoordinate, goal: Cartesian2DCoordinate) -> f64;
+}
+
+/// Distance along the x and y axes, the best estimate for moving between square cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Manhattan;
+impl Heuristic for Manhattan {
+    fn estimate(&self, from: Cartesian2DCoordinate, goal: Cartesian2DCoordinate) -> f64 {
+        f64::from(from.x.abs_diff(goal.x)) + f64::from(from.y.abs_diff(goal.y))
+    }
+}
+
+/// Straight line distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Euclidean;
+impl Heuristic for Euclidean {
+    fn estimate(&self, from: Cartesian2DCoordinate, goal: Cartesian2DCoordinate) -> f64 {
+        f64::from(from.x.abs_diff(goal.x)).hypot(f64::from(from.y.abs_diff(goal.y)))
+    }
+}
+
+/// No estimate, making the A* search a breadth first search that stops at the goal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroHeuristic;
+impl Heuristic for ZeroHeuristic {
+    fn estimate(&self, _: Cartesian2DCoordinate, _: Cartesian2DCoordinate) -> f64 {
+        0.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AStarSearch<Coord> {
+    /// The path from the start to the goal including both, `None` if the goal is unreachable.
+    pub path: Option<Vec<Coord>>,
+    /// Count of cells whose links were searched.
+    pub expanded_nodes: usize,
+    /// The most cells waiting to be searched at any one time.
+    pub max_open_nodes: usize,
+}
+
+/// Find a shortest path between two cells, only searching as much of the grid as the heuristic needs.
+///
+/// Unlike `Distances::for_grid` followed by `shortest_path` this does not flood the whole grid, so it suits
+/// point to point queries on huge mazes. Every step counts as 1, ignoring the grid's cell and link weights.
+/// Returns `None` if the start or goal is not on the grid.
+pub fn astar<GridIndexType, CellT, Iters, H>(
+    grid: &Grid<GridIndexType, CellT, Iters>,
+    start: CellT::Coord,
+    goal: CellT::Coord,
+    heuristic: &H,
+) -> Option<AStarSearch<CellT::Coord>>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    H: Heuristic + ?Sized,
+{
+    if !grid.is_valid_coordinate(start) || !grid.is_valid_coordinate(goal) {
+        return None;
+    }
+
+    let goal_2d = goal.as_cartesian_2d();
+    // Where x is not comparable between rows only the distance between rows is estimated.
+    let position = |coord: CellT::Coord| {
+        let coord_2d = coord.as_cartesian_2d();
+        if CellT::columns_align_across_rows() {
+            coord_2d
+        } else {
+            Cartesian2DCoordinate::new(goal_2d.x, coord_2d.y)
+        }
+    };
+    // One step can cover more than one unit of the estimate, e.g. moving diagonally between hexagonal cells or
+    // tunnelling under a cell of a weave grid.
+    let origin = Cartesian2DCoordinate::new(0, 0);
+    let longest_step = CellT::link_offsets()
+        .iter()
+        .map(|&(x, y)| heuristic.estimate(origin, Cartesian2DCoordinate::new(x, y)))
+        .fold(0.0, f64::max);
+    let estimate = |coord: CellT::Coord| {
+        if longest_step > 0.0 {
+            heuristic.estimate(position(coord), goal_2d) / longest_step
+        } else {
+            0.0
+        }
+    };
+
+    // Steps from the start to each cell seen so far, and the cell each was reached from.
+    let mut steps: FnvHashMap<CellT::Coord, u32> = FnvHashMap::default();
+    let mut came_from: FnvHashMap<CellT::Coord, CellT::Coord> = FnvHashMap::default();
+    let mut open = BinaryHeap::new();
+    steps.insert(start, 0);
+    open.push(OpenNode {
+        estimated_total: estimate(start),
+        steps: 0,
+        coord: start,
+    });
+
+    let mut expanded_nodes = 0;
+    let mut max_open_nodes = open.len();
+    let mut found = false;
+
+    while let Some(OpenNode {
+        steps: steps_to_node,
+        coord,
+        ..
+    }) = open.pop()
+    {
+        // A cell is pushed again each time a shorter route to it is found, skip the outdated entries.
+        if steps.get(&coord).is_some_and(|&best| best < steps_to_node) {
+            continue;
+        }
+        if coord == goal {
+            found = true;
+            break;
+        }
+        expanded_nodes += 1;
+
+        let links = grid.links(coord).expect("Open cell has an invalid cell coordinate.");
+        for &link in &*links {
+            let steps_to_link = steps_to_node + 1;
+            if steps.get(&link).map_or(true, |&best| steps_to_link < best) {
+                steps.insert(link, steps_to_link);
+                came_from.insert(link, coord);
+                open.push(OpenNode {
+                    estimated_total: f64::from(steps_to_link) + estimate(link),
+                    steps: steps_to_link,
+                    coord: link,
+                });
+            }
+        }
+        max_open_nodes = cmp::max(max_open_nodes, open.len());
+    }
+
+    let path = if found {
+        let mut path = vec![goal];
+        let mut current = goal;
+        while let Some(&previous) = came_from.get(&current) {
+            path.push(previous);
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    } else {
+        None
+    };
+
+    Some(AStarSearch {
+        path,
+        expanded_nodes,
+        max_open_nodes,
+    })
+}
+
+/// A cell waiting to be searched, ordered so the max-heap `BinaryHeap` pops the lowest estimated total first, breaking
+/// ties in favour of the cell furthest from the start.
+#[derive(Debug)]
+struct OpenNode<Coord> {
+    estimated_total: f64,
+    steps: u32,
+    coord: Coord,
+}
+impl<Coord> Ord for OpenNode<Coord> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        other
+            .estimated_total
+            .total_cmp(&self.estimated_total)
+            .then_with(|| self.steps.cmp(&other.steps))
+    }
+}
+impl<Coord> PartialOrd for OpenNode<Coord> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<Coord> PartialEq for OpenNode<Coord> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+impl<Coord> Eq for OpenNode<Coord> {}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::cells::{Cartesian2DCoordinate, Cell, SquareCell};
-    use crate::grids::{small_rect_grid, SmallRectangularGrid};
+    use crate::cells::{Cartesian2DCoordinate, Cell, HexCell, PolarCell, SquareCell, WeaveCell};
+    use crate::generators;
+    use crate::grids::{hex_grid, polar_grid, small_rect_grid, weave_grid, SmallRectangularGrid};
 
     use crate::units;
     use quickcheck::quickcheck;
-    use std::u32;
+    use rand::{rngs::SmallRng, SeedableRng};
 
     fn small_grid(w: usize, h: usize) -> SmallRectangularGrid {
         small_rect_grid(units::RowLength(w), units::ColumnLength(h)).expect("grid dimensions too large for small grid")
@@ -279,6 +632,23 @@ mod tests {
         y: u32::MAX,
     };
 
+    #[test]
+    fn distances_json_round_trip() {
+        let mut g = small_grid(4, 3);
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(2));
+        let distances = small_distances(&g, Cartesian2DCoordinate::new(1, 1)).unwrap();
+
+        let json = serde_json::to_string(&distances).unwrap();
+        let loaded: SmallDistances = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.start(), distances.start());
+        assert_eq!(loaded.max(), distances.max());
+        assert_eq!(loaded.distances(), distances.distances());
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+
+        let not_from_start = r#"{"start":{"x":0,"y":0},"distances":[[{"x":0,"y":0},1]]}"#;
+        assert!(serde_json::from_str::<SmallDistances>(not_from_start).is_err());
+    }
+
     #[test]
     fn distances_construction_requires_valid_start_coordinate() {
         let g = small_grid(3, 3);
@@ -358,6 +728,198 @@ mod tests {
         assert_eq!(distances.max(), 2);
     }
 
+    #[test]
+    fn astar_finds_shortest_paths_with_any_heuristic() {
+        let mut g = small_grid(12, 12);
+        let mut rng = SmallRng::seed_from_u64(11);
+        generators::recursive_backtracker(&mut g, None, &mut rng);
+        generators::braid(&mut g, 0.5, &mut rng);
+        let start = Cartesian2DCoordinate::new(0, 0);
+        let goal = Cartesian2DCoordinate::new(11, 7);
+        let distances = small_distances(&g, start).unwrap();
+        let steps = usize::from(distances.distance_from_start_to(goal).unwrap());
+
+        let heuristics: [&dyn Heuristic; 3] = [&Manhattan, &Euclidean, &ZeroHeuristic];
+        let searches = heuristics
+            .iter()
+            .map(|heuristic| astar(&g, start, goal, *heuristic).unwrap())
+            .collect::<Vec<_>>();
+
+        for search in &searches {
+            let path = search.path.as_ref().unwrap();
+            assert_eq!(path.len(), steps + 1);
+            assert_eq!((path[0], path[steps]), (start, goal));
+            assert!(path.windows(2).all(|pair| g.is_linked(pair[0], pair[1])));
+            assert!(search.expanded_nodes <= g.size());
+        }
+        // Guided searches expand no more cells than the blind search.
+        assert!(searches[0].expanded_nodes <= searches[2].expanded_nodes);
+        assert!(searches[1].expanded_nodes <= searches[2].expanded_nodes);
+    }
+
+    #[test]
+    fn astar_finds_shortest_paths_through_weave_tunnels() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        for _ in 0..4 {
+            let mut g = weave_grid(units::RowLength(15), units::ColumnLength(15)).unwrap();
+            generators::recursive_backtracker(&mut g, None, &mut rng);
+            generators::braid(&mut g, 1.0, &mut rng);
+            let start = Cartesian2DCoordinate::new(0, 0);
+            let distances = Distances::<WeaveCell, u32>::for_grid(&g, start).unwrap();
+
+            for goal in g.iter() {
+                let steps = distances.distance_from_start_to(goal).unwrap() as usize;
+                for heuristic in [&Manhattan as &dyn Heuristic, &Euclidean] {
+                    let path = astar(&g, start, goal, heuristic).unwrap().path.unwrap();
+                    assert_eq!(path.len(), steps + 1, "{:?} to {:?}", start, goal);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn astar_finds_shortest_paths_on_hex_and_polar_grids() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut hex = hex_grid(units::RowLength(12), units::ColumnLength(12)).unwrap();
+        generators::recursive_backtracker(&mut hex, None, &mut rng);
+        generators::braid(&mut hex, 1.0, &mut rng);
+        let mut polar = polar_grid(units::RowsCount(8)).unwrap();
+        generators::recursive_backtracker(&mut polar, None, &mut rng);
+        generators::braid(&mut polar, 1.0, &mut rng);
+
+        for start in hex.iter().step_by(11) {
+            let distances = Distances::<HexCell, u32>::for_grid(&hex, start).unwrap();
+            for goal in hex.iter() {
+                let steps = distances.distance_from_start_to(goal).unwrap() as usize;
+                for heuristic in [&Manhattan as &dyn Heuristic, &Euclidean] {
+                    let path = astar(&hex, start, goal, heuristic).unwrap().path.unwrap();
+                    assert_eq!(path.len(), steps + 1, "{:?} to {:?}", start, goal);
+                }
+            }
+        }
+        for start in polar.iter().step_by(11) {
+            let distances = Distances::<PolarCell, u32>::for_grid(&polar, start).unwrap();
+            for goal in polar.iter() {
+                let steps = distances.distance_from_start_to(goal).unwrap() as usize;
+                for heuristic in [&Manhattan as &dyn Heuristic, &Euclidean] {
+                    let path = astar(&polar, start, goal, heuristic).unwrap().path.unwrap();
+                    assert_eq!(path.len(), steps + 1, "{:?} to {:?}", start, goal);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn astar_unreachable_and_invalid_points() {
+        let mut g = small_grid(3, 3);
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(0, 0), gc(1, 0)).expect("Link Failed");
+
+        let search = astar(&g, gc(0, 0), gc(2, 2), &Manhattan).unwrap();
+        assert_eq!(search.path, None);
+        assert_eq!(search.expanded_nodes, 2);
+
+        let search = astar(&g, gc(1, 0), gc(1, 0), &Manhattan).unwrap();
+        assert_eq!(search.path, Some(vec![gc(1, 0)]));
+        assert_eq!(search.expanded_nodes, 0);
+
+        assert!(astar(&g, gc(0, 0), OUT_OF_GRID_COORDINATE, &Manhattan).is_none());
+        assert!(astar(&g, OUT_OF_GRID_COORDINATE, gc(0, 0), &ZeroHeuristic).is_none());
+    }
+
+    #[test]
+    fn dijkstra_goes_around_costly_cells() {
+        // 0 - 1 - 2
+        // |       |
+        // 3 - 4 - 5
+        let mut g = small_grid(3, 2);
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        for (a, b) in [
+            (gc(0, 0), gc(1, 0)),
+            (gc(1, 0), gc(2, 0)),
+            (gc(0, 0), gc(0, 1)),
+            (gc(0, 1), gc(1, 1)),
+            (gc(1, 1), gc(2, 1)),
+            (gc(2, 1), gc(2, 0)),
+        ] {
+            g.link(a, b).expect("Link Failed");
+        }
+
+        // Unweighted, both routes are the same length.
+        let costs = dijkstra(&g, gc(0, 0)).unwrap();
+        assert_eq!(costs.distance_from_start_to(gc(2, 0)), Some(2));
+        assert_eq!(
+            *costs.distances(),
+            small_distances(&g, gc(0, 0))
+                .unwrap()
+                .distances()
+                .iter()
+                .map(|(c, d)| (*c, u32::from(*d)))
+                .collect::<FnvHashMap<_, _>>()
+        );
+
+        // Lava along the top.
+        g.set_cell_weight(gc(1, 0), 10);
+        let costs = dijkstra(&g, gc(0, 0)).unwrap();
+        assert_eq!(costs.distance_from_start_to(gc(2, 0)), Some(4));
+        assert_eq!(costs.distance_from_start_to(gc(1, 0)), Some(10));
+        assert_eq!(costs.max(), 10);
+        assert_eq!(
+            cheapest_path(&g, &costs, gc(2, 0)),
+            Some(vec![gc(0, 0), gc(0, 1), gc(1, 1), gc(2, 1), gc(2, 0)])
+        );
+
+        // A locked door along the bottom.
+        g.set_link_weight(gc(1, 1), gc(2, 1), 20);
+        let costs = dijkstra(&g, gc(0, 0)).unwrap();
+        assert_eq!(costs.distance_from_start_to(gc(2, 0)), Some(11));
+        assert_eq!(
+            cheapest_path(&g, &costs, gc(2, 0)),
+            Some(vec![gc(0, 0), gc(1, 0), gc(2, 0)])
+        );
+
+        assert!(dijkstra(&g, OUT_OF_GRID_COORDINATE).is_none());
+    }
+
+    #[test]
+    fn cheapest_path_to_unreachable_cell_is_none() {
+        let mut g = small_grid(3, 1);
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(0, 0), gc(1, 0)).expect("Link Failed");
+        let costs = dijkstra(&g, gc(0, 0)).unwrap();
+        assert_eq!(cheapest_path(&g, &costs, gc(2, 0)), None);
+        assert_eq!(cheapest_path(&g, &costs, gc(0, 0)), Some(vec![gc(0, 0)]));
+    }
+
+    #[test]
+    fn cheapest_path_through_zero_cost_cells() {
+        let mut g = small_grid(4, 1);
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link_all_neighbours().unwrap();
+        g.set_cell_weight(gc(1, 0), 0);
+        g.set_cell_weight(gc(2, 0), 0);
+
+        let costs = dijkstra(&g, gc(0, 0)).unwrap();
+        assert_eq!(costs.distance_from_start_to(gc(3, 0)), Some(1));
+        assert_eq!(
+            cheapest_path(&g, &costs, gc(3, 0)),
+            Some(vec![gc(0, 0), gc(1, 0), gc(2, 0), gc(3, 0)])
+        );
+        assert_eq!(
+            cheapest_path(&g, &costs, gc(2, 0)),
+            Some(vec![gc(0, 0), gc(1, 0), gc(2, 0)])
+        );
+
+        // Zero cost around a loop.
+        let mut g = small_grid(2, 2);
+        g.link_all_neighbours().unwrap();
+        for coord in [gc(0, 0), gc(1, 0), gc(0, 1), gc(1, 1)] {
+            g.set_cell_weight(coord, 0);
+        }
+        let costs = dijkstra(&g, gc(0, 0)).unwrap();
+        assert_eq!(cheapest_path(&g, &costs, gc(1, 1)).map(|path| path.len()), Some(3));
+    }
+
     #[test]
     fn quickcheck_experiment() {
         fn p(_: Vec<isize>) -> bool {
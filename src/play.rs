@@ -0,0 +1,138 @@
+use crate::{
+    cells::{Cartesian2DCoordinate, CompassPrimary, SquareCell},
+    grid::{Grid, IndexType},
+    grid_traits::GridIterators,
+};
+use std::time::{Duration, Instant};
+
+/// The state of a player finding their way through a maze from a start cell to an end cell.
+///
+/// The player can only move along the maze's passages. Every move is counted and the cells moved through are kept as
+/// a trail. The timer starts with the game and stops when the player reaches the end cell.
+#[derive(Debug)]
+pub struct MazeGame {
+    start: Cartesian2DCoordinate,
+    end: Cartesian2DCoordinate,
+    trail: Vec<Cartesian2DCoordinate>,
+    moves: u32,
+    started: Instant,
+    finished: Option<Duration>,
+}
+
+impl MazeGame {
+    pub fn new(start: Cartesian2DCoordinate, end: Cartesian2DCoordinate) -> MazeGame {
+        MazeGame {
+            start,
+            end,
+            trail: vec![start],
+            moves: 0,
+            started: Instant::now(),
+            finished: if start == end { Some(Duration::ZERO) } else { None },
+        }
+    }
+
+    /// Start again from the start cell.
+    pub fn restart(&mut self) {
+        *self = MazeGame::new(self.start, self.end);
+    }
+
+    /// Move the player one cell in the given direction if there is a passage that way.
+    /// Returns whether the player moved. The player cannot move once the game is won.
+    pub fn move_player<GridIndexType, Iters>(
+        &mut self,
+        grid: &Grid<GridIndexType, SquareCell, Iters>,
+        direction: CompassPrimary,
+    ) -> bool
+    where
+        GridIndexType: IndexType,
+        Iters: GridIterators<SquareCell>,
+    {
+        if self.is_won() || !grid.is_neighbour_linked(self.player(), direction) {
+            return false;
+        }
+
+        if let Some(next_cell) = grid.neighbour_at_direction(self.player(), direction) {
+            self.trail.push(next_cell);
+            self.moves += 1;
+            if next_cell == self.end {
+                self.finished = Some(self.started.elapsed());
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn player(&self) -> Cartesian2DCoordinate {
+        *self.trail.last().expect("the trail always has the start cell")
+    }
+    pub fn start(&self) -> Cartesian2DCoordinate {
+        self.start
+    }
+    pub fn end(&self) -> Cartesian2DCoordinate {
+        self.end
+    }
+    /// Every cell the player has been in, in the order moved through, including revisits.
+    pub fn trail(&self) -> &[Cartesian2DCoordinate] {
+        &self.trail
+    }
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+    pub fn is_won(&self) -> bool {
+        self.finished.is_some()
+    }
+    /// Time played so far, or the time taken to win.
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.started.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        grids::small_rect_grid,
+        units::{ColumnLength, RowLength},
+    };
+
+    #[test]
+    fn player_moves_only_along_passages() {
+        // 0 - 1
+        //     |
+        // 2   3
+        let mut g = small_rect_grid(RowLength(2), ColumnLength(2)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(0, 0), gc(1, 0)).unwrap();
+        g.link(gc(1, 0), gc(1, 1)).unwrap();
+        g.link(gc(0, 1), gc(1, 1)).unwrap();
+        let mut game = MazeGame::new(gc(0, 0), gc(0, 1));
+
+        assert!(!game.move_player(&g, CompassPrimary::South));
+        assert!(!game.move_player(&g, CompassPrimary::West));
+        assert_eq!(game.player(), gc(0, 0));
+        assert_eq!(game.moves(), 0);
+
+        assert!(game.move_player(&g, CompassPrimary::East));
+        assert!(game.move_player(&g, CompassPrimary::West));
+        assert!(game.move_player(&g, CompassPrimary::East));
+        assert!(game.move_player(&g, CompassPrimary::South));
+        assert!(!game.is_won());
+        assert!(game.move_player(&g, CompassPrimary::West));
+
+        assert!(game.is_won());
+        assert_eq!(game.moves(), 5);
+        assert_eq!(
+            game.trail(),
+            &[gc(0, 0), gc(1, 0), gc(0, 0), gc(1, 0), gc(1, 1), gc(0, 1)]
+        );
+        let winning_time = game.elapsed();
+        assert!(!game.move_player(&g, CompassPrimary::East));
+        assert_eq!(game.elapsed(), winning_time);
+
+        game.restart();
+        assert_eq!(game.player(), gc(0, 0));
+        assert_eq!(game.moves(), 0);
+        assert!(!game.is_won());
+    }
+}
@@ -1,45 +1,69 @@
 use crate::{
-    cells::{Cartesian2DCoordinate, Cell, CompassPrimary, SquareCell},
-    grid::{Grid, IndexType},
+    cells::{Cartesian2DCoordinate, Cell, CompassPrimary, SquareCell, WeaveCell},
+    generators::GenerationEvent,
+    grid::{Grid, IndexType, DEFAULT_CELL_WEIGHT},
     grid_traits::GridIterators,
-    pathing, sdl,
-    sdl::SdlSetup,
+    pathing,
+    play::MazeGame,
+    utils,
+    utils::FnvHashSet,
 };
 
-use lazy_static::*; // macro
-use sdl2::{
-    event::{Event, WindowEvent},
-    hint,
-    image::SaveSurface,
-    pixels::{Color, PixelFormatEnum},
-    rect::{Point, Rect},
-    render::Canvas,
-    surface::Surface,
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    error::{ImageFormatHint, UnsupportedError, UnsupportedErrorKind},
+    Delay, DynamicImage, Frame, ImageError, ImageFormat, ImageResult, Rgb, RgbImage,
 };
-use std::{cmp, path::Path};
-
-lazy_static! {
-    static ref BLACK: Color = Color::RGB(0, 0, 0);
-    static ref WHITE: Color = Color::RGB(0xff, 0xff, 0xff);
-    static ref RED: Color = Color::RGB(0xff, 0, 0);
-    static ref GREEN: Color = Color::RGB(0, 0xff, 0);
-    static ref BLUE: Color = Color::RGB(0, 0, 0xff);
-    static ref YELLOW: Color = Color::RGB(0xff, 0xff, 0);
-    static ref HOT_PINK: Color = Color::RGB(255, 105, 180);
-}
+use std::{
+    cmp,
+    fmt::Write,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "sdl")]
+use crate::sdl;
+#[cfg(feature = "sdl")]
+pub use crate::sdl::play_square_grid;
+
+const BLACK: Rgb<u8> = Rgb([0, 0, 0]);
+pub(crate) const WHITE: Rgb<u8> = Rgb([0xff, 0xff, 0xff]);
+const GREEN: Rgb<u8> = Rgb([0, 0xff, 0]);
+const BLUE: Rgb<u8> = Rgb([0, 0, 0xff]);
+const HOT_PINK: Rgb<u8> = Rgb([255, 105, 180]);
+const LIGHT_GREEN: Rgb<u8> = Rgb([0x90, 0xee, 0x90]);
+const GREY: Rgb<u8> = Rgb([0xc0, 0xc0, 0xc0]);
+const LAVA: Rgb<u8> = Rgb([0xff, 0x8c, 0]);
+
+// 25 animation frames per second.
+const ANIMATION_FRAME_MILLISECONDS: u32 = 40;
+
+// 5x7 bitmaps for the start and end markers, one byte per row with the leftmost pixel in bit 4.
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+const GLYPH_S: [u8; GLYPH_HEIGHT as usize] = [0b01110, 0b10001, 0b10000, 0b01110, 0b00001, 0b10001, 0b01110];
+const GLYPH_E: [u8; GLYPH_HEIGHT as usize] = [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111];
+const GLYPH_W: [u8; GLYPH_HEIGHT as usize] = [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010];
+const GLYPH_I: [u8; GLYPH_HEIGHT as usize] = [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110];
+const GLYPH_N: [u8; GLYPH_HEIGHT as usize] = [0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001, 0b10001];
 
 #[derive(Debug)]
 pub struct RenderOptions<'path, 'dist> {
+    #[cfg_attr(not(feature = "sdl"), allow(dead_code))]
     show_on_screen: bool,
     colour_distances: bool,
+    shade_weights: bool,
     mark_start_end: bool,
-    start: Option<Cartesian2DCoordinate>,
-    end: Option<Cartesian2DCoordinate>,
+    pub(crate) start: Option<Cartesian2DCoordinate>,
+    pub(crate) end: Option<Cartesian2DCoordinate>,
     show_path: bool,
     distances: Option<&'dist pathing::Distances<SquareCell, u32>>,
     output_file: Option<&'path Path>,
     path: Option<Vec<Cartesian2DCoordinate>>,
     cell_side_pixels_length: u8,
+    frames_per_step: u32,
+    hold_frames: u32,
 }
 
 #[derive(Debug)]
@@ -57,6 +81,7 @@ impl<'path, 'dist> RenderOptionsBuilder<'path, 'dist> {
             options: RenderOptions {
                 show_on_screen: false,
                 colour_distances: false,
+                shade_weights: false,
                 mark_start_end: false,
                 start: None,
                 end: None,
@@ -65,9 +90,12 @@ impl<'path, 'dist> RenderOptionsBuilder<'path, 'dist> {
                 output_file: None,
                 path: None,
                 cell_side_pixels_length: 10,
+                frames_per_step: 1,
+                hold_frames: 25,
             },
         }
     }
+    /// Show the maze in a window. Windows need the `sdl` feature, without it nothing is shown.
     pub fn show_on_screen(mut self, on: bool) -> RenderOptionsBuilder<'path, 'dist> {
         self.options.show_on_screen = on;
         self
@@ -76,6 +104,11 @@ impl<'path, 'dist> RenderOptionsBuilder<'path, 'dist> {
         self.options.colour_distances = on;
         self
     }
+    /// Shade the cells that cost more than the default to move into, darker the costlier.
+    pub fn shade_weights(mut self, on: bool) -> RenderOptionsBuilder<'path, 'dist> {
+        self.options.shade_weights = on;
+        self
+    }
     pub fn mark_start_end(mut self, on: bool) -> RenderOptionsBuilder<'path, 'dist> {
         self.options.mark_start_end = on;
         self
@@ -111,100 +144,523 @@ impl<'path, 'dist> RenderOptionsBuilder<'path, 'dist> {
         self.options.cell_side_pixels_length = cell_side_pixels_length;
         self
     }
+    pub fn frames_per_step(mut self, frames_per_step: u32) -> RenderOptionsBuilder<'path, 'dist> {
+        self.options.frames_per_step = frames_per_step;
+        self
+    }
+    pub fn hold_frames(mut self, hold_frames: u32) -> RenderOptionsBuilder<'path, 'dist> {
+        self.options.hold_frames = hold_frames;
+        self
+    }
     pub fn build(self) -> RenderOptions<'path, 'dist> {
         self.options
     }
 }
 
+/// Draw the maze, writing it as a PNG if there is an output file and showing it in a window if asked to.
+/// SDL is only initialised when showing the maze on screen.
 pub fn render_square_grid<GridIndexType, Iters>(grid: &Grid<GridIndexType, SquareCell, Iters>, options: &RenderOptions)
 where
     GridIndexType: IndexType,
     Iters: GridIterators<SquareCell>,
 {
-    let sdl_setup = sdl::init();
+    let maze_image = square_grid_image(grid, options);
+
+    if let Some(file_path) = options.output_file {
+        maze_image
+            .save_with_format(file_path, ImageFormat::Png)
+            .expect("Failed to save maze image");
+    }
+
+    #[cfg(feature = "sdl")]
+    if options.show_on_screen {
+        sdl::show_maze_on_screen(maze_image, &sdl::init());
+    }
+}
 
-    // Logically eg. 20x20 grid === 200 x 200 pixels + 32 on the sides (232x232).
-    // scaled to whatever the window size is, which maybe a different aspect ratio.
+/// Draw the maze to an in memory image without any display or SDL libraries.
+///
+/// Logically eg. a 20x20 grid with 10 pixel cells is 201x201 pixels, the extra pixel being the south and east
+/// boundary walls.
+pub fn square_grid_image<GridIndexType, Iters>(
+    grid: &Grid<GridIndexType, SquareCell, Iters>,
+    options: &RenderOptions,
+) -> RgbImage
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+{
     let (image_w, image_h) = maze_image_dimensions(grid, options);
+    let mut image = RgbImage::from_pixel(image_w, image_h, WHITE);
+    draw_maze(&mut image, grid, options);
+    image
+}
 
-    // The visualisation window size can be whatever size we want. If it uses auto scaling by setting a logical size
-    // we can easily have aspect ratio issues unless the logical size is the same aspect ratio as the image
-    // If we convert a large surface to a texture it will probably have that weird aspect ratio (probably not a power of 2)
-    // Specifically: when we copy the texture to the framebuffer, we do not do any rectangle clipping, the texture is
-    // stretched over the output window.
-
-    // We are limited to one renderer per window it seems, at least with the current rust bindings.
-    // We want a hardware accelerated window for displaying a maze that uses a texture for performance,
-    // but we want a software surface with the maze drawn to it so that we can use sdl2_image save_surface
-    // to write out a png.
-    // - Renderer::from_surface looks like what we want
-    // - Note renderer.into_surface()/into_window() is not what we want
-    //   Only extracts the window/surface that already used by the Renderer, basically dropping the other data
-    //   that the renderer uses.
-    // After rendering to the surface, we can create texture from surface and use a new 2nd renderer to
-    // display to a window
-    let software_surface = Surface::new(image_w, image_h, PixelFormatEnum::RGB888).expect("Surface creation failed.");
-    let mut surface_canvas = Canvas::from_surface(software_surface).expect("Software renderer creation failed.");
-
-    // Sets a device independent resolution for rendering.
-    // SDL scales to the actual window size, which may change if we allow resizing and is also
-    // unknown if we just drop into fullscreen.
-    // surface_canvas.set_logical_size(logical_w, logical_h).unwrap();
-
-    // 0 or 'nearest' == nearest pixel sampling
-    // 1 or 'linear' == linear filtering (supported by OpenGL and Direct3D)
-    // 2 or 'best' == anisotropic filtering (supported by Direct3D)
-    // The hint strings don't seem to be abstracted in the rust source at the moment but we can see
-    // the #defines at e.g. https://github.com/spurious/SDL-mirror/blob/master/include/SDL_hints.h
-    // SDL_HINT_RENDER_SCALE_QUALITY applies per texture, not per renderer.
-    hint::set("SDL_RENDER_SCALE_QUALITY", "1");
-
-    draw_maze(&mut surface_canvas, grid, options, &sdl_setup);
-
-    // Getting the surface from the renderer drops the renderer.
-    let maze_surface: Surface = surface_canvas.into_surface();
+/// Draw a weave maze, writing it as a PNG if there is an output file and showing it in a window if asked to.
+pub fn render_weave_grid<GridIndexType, Iters>(grid: &Grid<GridIndexType, WeaveCell, Iters>, options: &RenderOptions)
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<WeaveCell>,
+{
+    let maze_image = weave_grid_image(grid, options);
 
     if let Some(file_path) = options.output_file {
-        maze_surface.save(file_path).expect("Failed to save surface");
+        maze_image
+            .save_with_format(file_path, ImageFormat::Png)
+            .expect("Failed to save maze image");
     }
 
+    #[cfg(feature = "sdl")]
     if options.show_on_screen {
-        show_maze_on_screen(maze_surface, &sdl_setup);
+        sdl::show_maze_on_screen(maze_image, &sdl::init());
+    }
+}
+
+/// Draw a weave maze to an in memory image.
+///
+/// Each cell's walls are inset from the cell's edges so that the over/under crossings are visible: passages extend
+/// out to the cell edges, and a tunnel under a corridor shows as a gap in the corridor's walls either side of it.
+/// The given distances are measured on square grids, which have no tunnels, so distances are measured again on the weave
+/// grid from the start cell, the start of the given distances or the start of the path. The start and end are marked
+/// at the cells given, otherwise at the ends of the path, or the start and the furthest cell when there is no path.
+pub fn weave_grid_image<GridIndexType, Iters>(
+    grid: &Grid<GridIndexType, WeaveCell, Iters>,
+    options: &RenderOptions,
+) -> RgbImage
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<WeaveCell>,
+{
+    let wall_colour = BLUE;
+    let (image_w, image_h) = maze_image_dimensions(grid, options);
+    let mut image = RgbImage::from_pixel(image_w, image_h, WHITE);
+
+    let cell_size = i32::from(options.cell_side_pixels_length);
+    let inset = cmp::max(1, cell_size / 5);
+    let max_weight = if options.shade_weights {
+        max_cell_weight(grid)
+    } else {
+        DEFAULT_CELL_WEIGHT
+    };
+
+    let path = options.path.as_deref().unwrap_or_default();
+    let start = options
+        .start
+        .or_else(|| options.distances.map(|distances| distances.start()))
+        .or_else(|| path.first().cloned());
+    let distances = if options.colour_distances || options.mark_start_end {
+        start.and_then(|start| pathing::Distances::<WeaveCell, u32>::for_grid(grid, start))
+    } else {
+        None
+    };
+    let max_cell_distance = distances.as_ref().map_or(0, |distances| distances.max());
+
+    for cell in grid.iter() {
+        // The outer cell edges and the inset wall positions.
+        let (x1, y1) = (cell.x as i32 * cell_size, cell.y as i32 * cell_size);
+        let (x4, y4) = (x1 + cell_size, y1 + cell_size);
+        let (x2, y2, x3, y3) = (x1 + inset, y1 + inset, x4 - inset, y4 - inset);
+
+        if let (true, Some(distances)) = (options.colour_distances, &distances) {
+            // The cell maybe unreachable
+            let distance_to_cell = distances.distance_from_start_to(cell).unwrap_or(max_cell_distance);
+            let intensity = if max_cell_distance == 0 {
+                1.0
+            } else {
+                (max_cell_distance - distance_to_cell) as f32 / max_cell_distance as f32
+            };
+            let cell_colour = colour_mul(GREEN, intensity);
+            fill_rect(&mut image, x2, y2, (x3 - x2) as u32, (y3 - y2) as u32, cell_colour);
+        }
+
+        if options.shade_weights {
+            if let Some(weight_colour) = cell_weight_colour(grid.cell_weight(cell), max_weight) {
+                fill_rect(&mut image, x2, y2, (x3 - x2) as u32, (y3 - y2) as u32, weight_colour);
+            }
+        }
+
+        if WeaveCell::is_passage_towards(grid, cell, CompassPrimary::North) {
+            draw_line(&mut image, (x2, y1), (x2, y2), wall_colour);
+            draw_line(&mut image, (x3, y1), (x3, y2), wall_colour);
+        } else {
+            draw_line(&mut image, (x2, y2), (x3, y2), wall_colour);
+        }
+        if WeaveCell::is_passage_towards(grid, cell, CompassPrimary::South) {
+            draw_line(&mut image, (x2, y3), (x2, y4), wall_colour);
+            draw_line(&mut image, (x3, y3), (x3, y4), wall_colour);
+        } else {
+            draw_line(&mut image, (x2, y3), (x3, y3), wall_colour);
+        }
+        if WeaveCell::is_passage_towards(grid, cell, CompassPrimary::West) {
+            draw_line(&mut image, (x1, y2), (x2, y2), wall_colour);
+            draw_line(&mut image, (x1, y3), (x2, y3), wall_colour);
+        } else {
+            draw_line(&mut image, (x2, y2), (x2, y3), wall_colour);
+        }
+        if WeaveCell::is_passage_towards(grid, cell, CompassPrimary::East) {
+            draw_line(&mut image, (x3, y2), (x4, y2), wall_colour);
+            draw_line(&mut image, (x3, y3), (x4, y3), wall_colour);
+        } else {
+            draw_line(&mut image, (x3, y2), (x3, y3), wall_colour);
+        }
+
+        // The walls of a tunnel beneath the cell only show in the inset margins, hidden under the corridor above.
+        if WeaveCell::is_tunnelled_under(grid, cell, CompassPrimary::North) {
+            draw_line(&mut image, (x2, y1), (x2, y2), wall_colour);
+            draw_line(&mut image, (x3, y1), (x3, y2), wall_colour);
+            draw_line(&mut image, (x2, y3), (x2, y4), wall_colour);
+            draw_line(&mut image, (x3, y3), (x3, y4), wall_colour);
+        } else if WeaveCell::is_tunnelled_under(grid, cell, CompassPrimary::East) {
+            draw_line(&mut image, (x1, y2), (x2, y2), wall_colour);
+            draw_line(&mut image, (x1, y3), (x2, y3), wall_colour);
+            draw_line(&mut image, (x3, y2), (x4, y2), wall_colour);
+            draw_line(&mut image, (x3, y3), (x4, y3), wall_colour);
+        }
+    }
+
+    let cell_centre = |cell: Cartesian2DCoordinate| {
+        (
+            cell.x as i32 * cell_size + cell_size / 2,
+            cell.y as i32 * cell_size + cell_size / 2,
+        )
+    };
+
+    if options.mark_start_end {
+        // Start and end symbol letters scaled up by whole pixels to roughly 80% of the cell size.
+        let glyph_scale = cmp::max(1, (cell_size as u32 * 4 / 5) / GLYPH_HEIGHT);
+        let furthest = distances
+            .as_ref()
+            .and_then(|distances| distances.furthest_points_on_grid().first().cloned());
+        if let Some(start) = start {
+            draw_glyph(&mut image, &GLYPH_S, cell_centre(start), glyph_scale, BLACK);
+        }
+        if let Some(end) = options.end.or_else(|| path.last().cloned()).or(furthest) {
+            let end_colour = if options.colour_distances { WHITE } else { BLACK };
+            draw_glyph(&mut image, &GLYPH_E, cell_centre(end), glyph_scale, end_colour);
+        }
+    }
+
+    if let Some(path) = visible_path(options) {
+        for segment in path.windows(2) {
+            draw_line(&mut image, cell_centre(segment[0]), cell_centre(segment[1]), HOT_PINK);
+        }
+    }
+
+    image
+}
+
+/// Draw the maze as SVG text. SVG is a vector format so scales up to any print size without blurring, the cell side
+/// length only fixes the initial display size.
+///
+/// Cell distances are filled rectangles, the walls are one path, the solution path is a polyline and the start and
+/// end markers are text.
+pub fn square_grid_svg<GridIndexType, Iters>(
+    grid: &Grid<GridIndexType, SquareCell, Iters>,
+    options: &RenderOptions,
+) -> String
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+{
+    let (image_w, image_h) = maze_image_dimensions(grid, options);
+    let cell_size = u32::from(options.cell_side_pixels_length);
+    let cell_corners = |cell: Cartesian2DCoordinate| {
+        let (x1, y1) = (cell.x * cell_size, cell.y * cell_size);
+        (x1, y1, x1 + cell_size, y1 + cell_size)
+    };
+    let cell_centre = |cell: Cartesian2DCoordinate| {
+        let half_cell = cell_size as f32 / 2.0;
+        (
+            (cell.x * cell_size) as f32 + half_cell,
+            (cell.y * cell_size) as f32 + half_cell,
+        )
+    };
+    let svg_colour = |Rgb([r, g, b]): Rgb<u8>| format!("#{:02x}{:02x}{:02x}", r, g, b);
+
+    let max_cell_distance = options.distances.map_or(0, |dist| dist.max());
+    let distance_to = |cell| {
+        options.distances.map_or(0, |dist| {
+            // The cell maybe unreachable
+            dist.distance_from_start_to(cell).unwrap_or(max_cell_distance)
+        })
+    };
+
+    // Writing to a String cannot fail, so the fmt::Results are ignored.
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = image_w,
+        h = image_h
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        svg_colour(WHITE)
+    );
+    // Shift half a unit so that one unit wide lines cover whole pixels when rasterised at the default size.
+    let _ = writeln!(svg, r#"<g transform="translate(0.5 0.5)">"#);
+
+    if options.colour_distances {
+        let _ = writeln!(svg, r#"<g shape-rendering="crispEdges">"#);
+        for cell in grid.iter() {
+            let intensity = (max_cell_distance as f32 - distance_to(cell) as f32) / max_cell_distance as f32;
+            let (x1, y1, _, _) = cell_corners(cell);
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}"/>"#,
+                x1,
+                y1,
+                svg_colour(colour_mul(GREEN, intensity)),
+                s = cell_size
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    if options.shade_weights {
+        let max_weight = max_cell_weight(grid);
+        let _ = writeln!(svg, r#"<g shape-rendering="crispEdges">"#);
+        for cell in grid.iter() {
+            if let Some(colour) = cell_weight_colour(grid.cell_weight(cell), max_weight) {
+                let (x1, y1, _, _) = cell_corners(cell);
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}"/>"#,
+                    x1,
+                    y1,
+                    svg_colour(colour),
+                    s = cell_size
+                );
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    let mut walls = String::new();
+    for cell in grid.iter() {
+        let (x1, y1, x2, y2) = cell_corners(cell);
+        if grid.neighbour_at_direction(cell, CompassPrimary::North).is_none() {
+            let _ = write!(walls, "M{} {}H{}", x1, y1, x2);
+        }
+        if grid.neighbour_at_direction(cell, CompassPrimary::West).is_none() {
+            let _ = write!(walls, "M{} {}V{}", x1, y1, y2);
+        }
+        if must_draw_wall(grid, cell, CompassPrimary::East) {
+            let _ = write!(walls, "M{} {}V{}", x2, y1, y2);
+        }
+        if must_draw_wall(grid, cell, CompassPrimary::South) {
+            let _ = write!(walls, "M{} {}H{}", x1, y2, x2);
+        }
+    }
+    let _ = writeln!(
+        svg,
+        r#"<path d="{}" fill="none" stroke="{}" stroke-width="1" stroke-linecap="square"/>"#,
+        walls,
+        svg_colour(BLUE)
+    );
+
+    if let Some(path) = visible_path(options) {
+        let points = path
+            .iter()
+            .map(|&cell| {
+                let (x, y) = cell_centre(cell);
+                format!("{},{}", x, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1"/>"#,
+            points,
+            svg_colour(HOT_PINK)
+        );
+    }
+
+    if options.mark_start_end {
+        let font_size = cell_size as f32 * 0.8;
+        let mut marker = |text, cell, colour| {
+            let (x, y) = cell_centre(cell);
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" font-family="Roboto, sans-serif" font-weight="bold" font-size="{}" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+                x,
+                y,
+                font_size,
+                svg_colour(colour),
+                text
+            );
+        };
+        for cell in grid.iter() {
+            let distance_to_cell = distance_to(cell);
+            if is_start_cell(options, cell, distance_to_cell) {
+                marker("S", cell, BLACK);
+            }
+            if is_end_cell(options, cell, distance_to_cell, max_cell_distance) {
+                marker("E", cell, if options.colour_distances { WHITE } else { BLACK });
+            }
+        }
+    }
+
+    let _ = writeln!(svg, "</g>");
+    let _ = writeln!(svg, "</svg>");
+    svg
+}
+
+/// Write an animation of the maze being generated by replaying the generator's steps onto the grid, which should
+/// start without any links, e.g. the steps observed by a `generators::ObservedGrid` of another grid of the same size.
+///
+/// A `.gif` output file is an animated GIF, a `.png` output file is a sequence of PNG frames numbered after it, e.g.
+/// maze_00000.png, maze_00001.png, etc. Each step is shown for `frames_per_step` frames, highlighting the cell the
+/// generator is at and its frontier: the active cells of generators that backtrack, otherwise the current random
+/// walk. Cells that have not been reached yet are grey. The finished maze, drawn with all the render options, is
+/// then held for `hold_frames` frames.
+pub fn render_square_grid_animation<GridIndexType, Iters, Steps>(
+    grid: &mut Grid<GridIndexType, SquareCell, Iters>,
+    steps: Steps,
+    options: &RenderOptions,
+    output_file: &Path,
+) -> ImageResult<()>
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+    Steps: IntoIterator<Item = GenerationEvent<Cartesian2DCoordinate>>,
+{
+    let mut writer = AnimationWriter::new(output_file)?;
+
+    let steps: Vec<GenerationEvent<Cartesian2DCoordinate>> = steps.into_iter().collect();
+    let frontier_is_active_cells = steps.iter().any(|step| matches!(step, GenerationEvent::Backtrack(_)));
+    let step_options = RenderOptionsBuilder::new()
+        .cell_side_pixels_length(options.cell_side_pixels_length)
+        .build();
+    let cell_size = i32::from(options.cell_side_pixels_length);
+
+    let mut frontier: FnvHashSet<Cartesian2DCoordinate> = utils::fnv_hashset(grid.size());
+
+    for step in steps {
+        step.apply(grid);
+        let current_cell = match step {
+            GenerationEvent::Visit(cell) => {
+                if frontier_is_active_cells {
+                    frontier.insert(cell);
+                } else {
+                    // Joined the maze at the end of a random walk.
+                    frontier.remove(&cell);
+                }
+                Some(cell)
+            }
+            GenerationEvent::Link(_, cell) | GenerationEvent::Unlink(cell, _) => Some(cell),
+            GenerationEvent::Backtrack(cell) => {
+                frontier.remove(&cell);
+                Some(cell)
+            }
+            GenerationEvent::Walk(cell) => {
+                frontier.insert(cell);
+                Some(cell)
+            }
+            GenerationEvent::EraseLoop(cells) => {
+                for cell in &cells {
+                    frontier.remove(cell);
+                }
+                None
+            }
+        };
+
+        let mut frame = square_grid_image(grid, &step_options);
+        for cell in grid.iter() {
+            let highlight = if current_cell == Some(cell) {
+                Some(HOT_PINK)
+            } else if frontier.contains(&cell) {
+                Some(LIGHT_GREEN)
+            } else if grid.links(cell).is_some_and(|links| links.is_empty()) {
+                Some(GREY)
+            } else {
+                None
+            };
+            if let Some(colour) = highlight {
+                // Inside the cell's walls.
+                let (x, y) = (cell.x as i32 * cell_size + 1, cell.y as i32 * cell_size + 1);
+                let side = (cell_size - 1) as u32;
+                fill_rect(&mut frame, x, y, side, side, colour);
+            }
+        }
+        writer.write_frame(&frame, options.frames_per_step)?;
+    }
+
+    writer.write_frame(&square_grid_image(grid, options), options.hold_frames)
+}
+
+/// Writes the frames of an animation, either to an animated GIF or as numbered PNG files.
+enum AnimationWriter {
+    Gif(GifEncoder<BufWriter<File>>),
+    PngFrames { first_frame: PathBuf, frames_count: usize },
+}
+
+impl AnimationWriter {
+    fn new(output_file: &Path) -> ImageResult<AnimationWriter> {
+        match ImageFormat::from_path(output_file)? {
+            ImageFormat::Gif => {
+                let mut encoder = GifEncoder::new(BufWriter::new(File::create(output_file)?));
+                encoder.set_repeat(Repeat::Infinite)?;
+                Ok(AnimationWriter::Gif(encoder))
+            }
+            ImageFormat::Png => Ok(AnimationWriter::PngFrames {
+                first_frame: output_file.to_path_buf(),
+                frames_count: 0,
+            }),
+            other => Err(ImageError::Unsupported(UnsupportedError::from_format_and_kind(
+                ImageFormatHint::Exact(other),
+                UnsupportedErrorKind::Format(ImageFormatHint::Exact(other)),
+            ))),
+        }
+    }
+
+    /// Show the image for the given number of frames, writing nothing for 0 frames.
+    fn write_frame(&mut self, image: &RgbImage, frames: u32) -> ImageResult<()> {
+        if frames == 0 {
+            return Ok(());
+        }
+
+        match self {
+            AnimationWriter::Gif(encoder) => {
+                // A GIF frame can be shown for any number of frame durations.
+                let rgba = DynamicImage::ImageRgb8(image.clone()).into_rgba8();
+                let delay = Delay::from_numer_denom_ms(ANIMATION_FRAME_MILLISECONDS * frames, 1);
+                encoder.encode_frame(Frame::from_parts(rgba, 0, 0, delay))
+            }
+            AnimationWriter::PngFrames {
+                first_frame,
+                frames_count,
+            } => {
+                let stem = first_frame
+                    .file_stem()
+                    .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+                for _ in 0..frames {
+                    let frame_path = first_frame.with_file_name(format!("{}_{:05}.png", stem, frames_count));
+                    image.save_with_format(frame_path, ImageFormat::Png)?;
+                    *frames_count += 1;
+                }
+                Ok(())
+            }
+        }
     }
 }
 
 fn draw_maze<GridIndexType, Iters>(
-    canvas: &mut Canvas<Surface>,
+    image: &mut RgbImage,
     grid: &Grid<GridIndexType, SquareCell, Iters>,
     options: &RenderOptions,
-    sdl_setup: &SdlSetup,
 ) where
     GridIndexType: IndexType,
     Iters: GridIterators<SquareCell>,
 {
-    // clear the texture background to white
-    canvas.set_draw_color(*WHITE);
-    canvas.clear();
-
-    let distance_colour = *GREEN;
-    let wall_colour = *BLUE;
-    canvas.set_draw_color(wall_colour);
+    let distance_colour = GREEN;
+    let wall_colour = BLUE;
 
     let cell_size_pixels = options.cell_side_pixels_length as usize;
 
-    // Font creation
-    let font_path: &Path = Path::new("resources/Roboto-Regular.ttf");
-    let font_px_size = ((cell_size_pixels as f32) * 0.8) as u16;
-    let mut font = sdl_setup
-        .ttf_context
-        .load_font(font_path, font_px_size)
-        .expect("Failed to load font");
-    font.set_style(sdl2::ttf::FontStyle::BOLD);
-
-    // Start and end symbol letters rendered to different surfaces
-    let s_surface = font.render("S").blended(*BLACK).unwrap();
-    let e_white_surface = font.render("E").blended(*WHITE).unwrap();
-    let e_black_surface = font.render("E").blended(*BLACK).unwrap();
+    // Start and end symbol letters scaled up by whole pixels to roughly 80% of the cell size.
+    let glyph_scale = cmp::max(1, (cell_size_pixels as u32 * 4 / 5) / GLYPH_HEIGHT);
 
     let calc_cell_screen_coordinates = |cell_coord: Cartesian2DCoordinate| -> (i32, i32, i32, i32) {
         let column = cell_coord.x as usize;
@@ -222,42 +678,31 @@ fn draw_maze<GridIndexType, Iters>(
         0
     };
     let max_cell_distance_f: f32 = max_cell_distance as f32;
+    let max_weight = if options.shade_weights {
+        max_cell_weight(grid)
+    } else {
+        DEFAULT_CELL_WEIGHT
+    };
 
     for cell in grid.iter() {
         let (x1, y1, x2, y2) = calc_cell_screen_coordinates(cell);
 
         // special cases north and west to handle first row and column.
         if grid.neighbour_at_direction(cell, CompassPrimary::North).is_none() {
-            canvas.draw_line(Point::new(x1, y1), Point::new(x2, y1)).unwrap();
+            draw_line(image, (x1, y1), (x2, y1), wall_colour);
         }
         if grid.neighbour_at_direction(cell, CompassPrimary::West).is_none() {
-            canvas.draw_line(Point::new(x1, y1), Point::new(x1, y2)).unwrap();
+            draw_line(image, (x1, y1), (x1, y2), wall_colour);
         }
 
-        // We don't want to draw unnecessary walls for cells that cannot be accessed, so if there are no links to a cell
-        // and no links to the neighbour it shares a wall with then the wall need not be drawn.
-        let are_links_count_of_valid_cells_zero =
-            |c: Cartesian2DCoordinate, neighbour_direction: CompassPrimary| -> bool {
-                let cell_links_count_is_zero = |c| grid.links(c).map_or(false, |linked_cells| linked_cells.is_empty());
-
-                if cell_links_count_is_zero(c) {
-                    grid.neighbour_at_direction(c, neighbour_direction)
-                        .map_or(false, cell_links_count_is_zero)
-                } else {
-                    false
-                }
-            };
-
-        let must_draw_east_wall = !grid.is_neighbour_linked(cell, CompassPrimary::East)
-            && !are_links_count_of_valid_cells_zero(cell, CompassPrimary::East);
-        let must_draw_south_wall = !grid.is_neighbour_linked(cell, CompassPrimary::South)
-            && !are_links_count_of_valid_cells_zero(cell, CompassPrimary::South);
+        let must_draw_east_wall = must_draw_wall(grid, cell, CompassPrimary::East);
+        let must_draw_south_wall = must_draw_wall(grid, cell, CompassPrimary::South);
 
         if must_draw_east_wall {
-            canvas.draw_line(Point::new(x2, y1), Point::new(x2, y2)).unwrap();
+            draw_line(image, (x2, y1), (x2, y2), wall_colour);
         }
         if must_draw_south_wall {
-            canvas.draw_line(Point::new(x1, y2), Point::new(x2, y2)).unwrap();
+            draw_line(image, (x1, y2), (x2, y2), wall_colour);
         }
 
         let distance_to_cell = if let Some(dist) = options.distances {
@@ -268,7 +713,7 @@ fn draw_maze<GridIndexType, Iters>(
         };
         let distance_to_cell_f = distance_to_cell as f32;
 
-        if options.colour_distances || options.mark_start_end {
+        if options.colour_distances || options.shade_weights || options.mark_start_end {
             // Pixels on which to draw a particular cell
             // offset inside the wall line
             let cell_x1 = x1 + 1;
@@ -286,154 +731,234 @@ fn draw_maze<GridIndexType, Iters>(
 
                 // let cell_colour = rainbow_colour(intensity);
 
-                canvas.set_draw_color(cell_colour);
-                let cell_bg_rect = Rect::new(cell_x1, cell_y1, w, h);
-                canvas.fill_rect(cell_bg_rect).unwrap();
-                canvas.set_draw_color(wall_colour);
+                fill_rect(image, cell_x1, cell_y1, w, h, cell_colour);
+            }
+
+            if options.shade_weights {
+                if let Some(weight_colour) = cell_weight_colour(grid.cell_weight(cell), max_weight) {
+                    fill_rect(image, cell_x1, cell_y1, w, h, weight_colour);
+                }
             }
 
             if options.mark_start_end {
-                // Start?
-                let is_start = if let Some(start_coord) = options.start {
-                    start_coord == cell
-                } else {
-                    distance_to_cell == 0
-                };
-                if is_start {
-                    s_surface
-                        .blit(
-                            None,
-                            canvas.surface_mut(),
-                            Some(Rect::new(cell_x1 + 1, cell_y1 - 1, w - 1, h - 1)),
-                        )
-                        .expect("S blit to maze surface failed");
+                let cell_centre = (x1 + (x2 - x1) / 2, y1 + (y2 - y1) / 2);
+
+                if is_start_cell(options, cell, distance_to_cell) {
+                    draw_glyph(image, &GLYPH_S, cell_centre, glyph_scale, BLACK);
                 }
 
-                let is_end = if let Some(end_coord) = options.end {
-                    end_coord == cell
-                } else {
-                    distance_to_cell == max_cell_distance
-                };
-                if is_end {
-                    let end_surface = if options.colour_distances {
-                        &e_white_surface
-                    } else {
-                        &e_black_surface
-                    };
-                    end_surface
-                        .blit(
-                            None,
-                            canvas.surface_mut(),
-                            Some(Rect::new(cell_x1 + 1, cell_y1 - 1, w - 1, h - 1)),
-                        )
-                        .expect("E blit to maze surface failed");
+                if is_end_cell(options, cell, distance_to_cell, max_cell_distance) {
+                    let end_colour = if options.colour_distances { WHITE } else { BLACK };
+                    draw_glyph(image, &GLYPH_E, cell_centre, glyph_scale, end_colour);
                 }
             }
         }
     }
 
-    if let Some(ref path) = options.path {
-        let path_long_enough_to_show = |path: &[Cartesian2DCoordinate], options: &RenderOptions| -> bool {
-            if options.mark_start_end {
-                path.len() >= 4
-            } else {
-                path.len() >= 2
-            }
+    if let Some(path) = visible_path(options) {
+        let calc_cell_centre_screen_coordinate = |cell| {
+            let (x1, y1, x2, y2) = calc_cell_screen_coordinates(cell);
+            let half_w = (x2 - x1) / 2;
+            let half_h = (y2 - y1) / 2;
+            let mid_x = x1 + half_w;
+            let mid_y = y1 + half_h;
+            (mid_x, mid_y)
         };
 
-        if path_long_enough_to_show(path, options) {
-            let calc_cell_centre_screen_coordinate = |cell| {
-                let (x1, y1, x2, y2) = calc_cell_screen_coordinates(cell);
-                let half_w = (x2 - x1) / 2;
-                let half_h = (y2 - y1) / 2;
-                let mid_x = x1 + half_w;
-                let mid_y = y1 + half_h;
-                (mid_x, mid_y)
-            };
+        for segment in path.windows(2) {
+            let from = calc_cell_centre_screen_coordinate(segment[0]);
+            let to = calc_cell_centre_screen_coordinate(segment[1]);
+            draw_line(image, from, to, HOT_PINK);
+        }
+    }
+}
 
-            canvas.set_draw_color(*HOT_PINK);
+/// We don't want to draw unnecessary walls for cells that cannot be accessed, so if there are no links to a cell
+/// and no links to the neighbour it shares a wall with then the wall need not be drawn.
+fn must_draw_wall<GridIndexType, Iters>(
+    grid: &Grid<GridIndexType, SquareCell, Iters>,
+    cell: Cartesian2DCoordinate,
+    neighbour_direction: CompassPrimary,
+) -> bool
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+{
+    let cell_links_count_is_zero = |c| grid.links(c).is_some_and(|linked_cells| linked_cells.is_empty());
+    let are_links_count_of_valid_cells_zero = cell_links_count_is_zero(cell)
+        && grid
+            .neighbour_at_direction(cell, neighbour_direction)
+            .is_some_and(cell_links_count_is_zero);
 
-            let (skip_amount, take_amount) = if options.mark_start_end {
-                (1, path.len() - 2)
-            } else {
-                (0, path.len())
-            };
-            let mut last_cell_draw_pos = calc_cell_centre_screen_coordinate(path[skip_amount]);
-            let mut path_draw_buffer = Vec::<Point>::with_capacity(take_amount - skip_amount);
+    !grid.is_neighbour_linked(cell, neighbour_direction) && !are_links_count_of_valid_cells_zero
+}
 
-            for cell in path.iter().skip(skip_amount).take(take_amount) {
-                let path_line_point_1 = last_cell_draw_pos;
-                let path_line_point_2 = calc_cell_centre_screen_coordinate(*cell);
+fn is_start_cell(options: &RenderOptions, cell: Cartesian2DCoordinate, distance_to_cell: u32) -> bool {
+    if let Some(start_coord) = options.start {
+        start_coord == cell
+    } else {
+        distance_to_cell == 0
+    }
+}
 
-                path_draw_buffer.extend([Point::from(path_line_point_1), Point::from(path_line_point_2)]);
+fn is_end_cell(
+    options: &RenderOptions,
+    cell: Cartesian2DCoordinate,
+    distance_to_cell: u32,
+    max_cell_distance: u32,
+) -> bool {
+    if let Some(end_coord) = options.end {
+        end_coord == cell
+    } else {
+        distance_to_cell == max_cell_distance
+    }
+}
 
-                last_cell_draw_pos = path_line_point_2;
-            }
+/// The part of the path that is drawn, which stops short of the start and end cells when they are marked.
+fn visible_path<'a>(options: &'a RenderOptions) -> Option<&'a [Cartesian2DCoordinate]> {
+    let path = options.path.as_deref()?;
+    if options.mark_start_end {
+        if path.len() >= 4 {
+            Some(&path[1..path.len() - 1])
+        } else {
+            None
+        }
+    } else if path.len() >= 2 {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Draw a line including both end points, ignoring any pixels outside of the image.
+fn draw_line(image: &mut RgbImage, from: (i32, i32), to: (i32, i32), colour: Rgb<u8>) {
+    // Bresenham's line algorithm, generalised to all octants.
+    let (mut x, mut y) = from;
+    let (x2, y2) = to;
+    let dx = (x2 - x).abs();
+    let dy = -(y2 - y).abs();
+    let step_x = if x < x2 { 1 } else { -1 };
+    let step_y = if y < y2 { 1 } else { -1 };
+    let mut error = dx + dy;
 
-            canvas.draw_lines(path_draw_buffer.as_slice()).unwrap();
+    loop {
+        put_pixel(image, x, y, colour);
+        if x == x2 && y == y2 {
+            break;
+        }
+        let error_2 = 2 * error;
+        if error_2 >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if error_2 <= dx {
+            error += dx;
+            y += step_y;
         }
     }
 }
 
-fn show_maze_on_screen(maze_surface: Surface, sdl_setup: &SdlSetup) {
-    // Fit the window size to the texture unless the texture is bigger than the display resolution
-    let primary_display_mode = sdl_setup.video_subsystem.current_display_mode(0).unwrap();
-    let (maze_w, maze_h) = (maze_surface.width(), maze_surface.height());
-    let (display_w, display_h) = (primary_display_mode.w as u32, primary_display_mode.h as u32);
-    let maze_image_padding = 32;
-    let window_w = cmp::min(display_w, maze_w + maze_image_padding);
-    let window_h = cmp::min(display_h, maze_h + maze_image_padding);
-
-    let mut window_builder = sdl_setup.video_subsystem.window("Mazes", window_w, window_h);
-    let window = window_builder
-        .position_centered()
-        .resizable()
-        .allow_highdpi()
-        .build()
-        .unwrap();
-    let mut canvas = window
-        .into_canvas()
-        .present_vsync()
-        .accelerated()
-        .target_texture()
-        .build()
-        .unwrap();
-    let texture_creator = canvas.texture_creator();
-
-    let maze_texture = texture_creator.create_texture_from_surface(maze_surface).unwrap();
-    let mut maze_target_rect = centre_rectangle(maze_w, maze_h, window_w, window_h);
-
-    let mut events = sdl_setup.sdl_context.event_pump().unwrap();
-    'running: loop {
-        for event in events.poll_iter() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(sdl2::keyboard::Keycode::Q),
-                    ..
-                } => break 'running,
-                Event::Window {
-                    win_event: WindowEvent::Resized(new_width, new_height),
-                    ..
-                } => {
-                    maze_target_rect = centre_rectangle(maze_w, maze_h, new_width as u32, new_height as u32);
-                }
-                _ => continue,
-                // todo allow resolution > display size?
-                // todo allow control of max on screen window size
+fn fill_rect(image: &mut RgbImage, x: i32, y: i32, w: u32, h: u32, colour: Rgb<u8>) {
+    for py in y..y + h as i32 {
+        for px in x..x + w as i32 {
+            put_pixel(image, px, py, colour);
+        }
+    }
+}
+
+/// Draw a bitmap glyph centred on a point, each glyph pixel becoming a `scale` x `scale` square.
+fn draw_glyph(
+    image: &mut RgbImage,
+    glyph: &[u8; GLYPH_HEIGHT as usize],
+    centre: (i32, i32),
+    scale: u32,
+    colour: Rgb<u8>,
+) {
+    let left = centre.0 - (GLYPH_WIDTH * scale / 2) as i32;
+    let top = centre.1 - (GLYPH_HEIGHT * scale / 2) as i32;
+
+    for (row, bits) in glyph.iter().enumerate() {
+        for column in 0..GLYPH_WIDTH {
+            if bits & (1 << (GLYPH_WIDTH - 1 - column)) != 0 {
+                let px = left + (column * scale) as i32;
+                let py = top + (row as u32 * scale) as i32;
+                fill_rect(image, px, py, scale, scale, colour);
             }
         }
+    }
+}
 
-        canvas.set_draw_color(*WHITE);
-        canvas.clear();
-        canvas
-            .copy(&maze_texture, None, Some(maze_target_rect))
-            .expect("Maze texture copy failed.");
-        canvas.present();
+#[inline]
+fn put_pixel(image: &mut RgbImage, x: i32, y: i32, colour: Rgb<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < image.width() && (y as u32) < image.height() {
+        image.put_pixel(x as u32, y as u32, colour);
     }
 }
 
-fn maze_image_dimensions<GridIndexType, CellT, Iters>(
+/// Draw the maze with the player, their trail and the end point, adding a win banner once the game is won.
+#[cfg_attr(not(feature = "sdl"), allow(dead_code))]
+pub(crate) fn play_frame_image<GridIndexType, Iters>(
+    grid: &Grid<GridIndexType, SquareCell, Iters>,
+    game: &MazeGame,
+    options: &RenderOptions,
+) -> RgbImage
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+{
+    let mut image = square_grid_image(grid, options);
+    let cell_size = i32::from(options.cell_side_pixels_length);
+    let cell_origin = |cell: Cartesian2DCoordinate| (cell.x as i32 * cell_size, cell.y as i32 * cell_size);
+
+    for &cell in game.trail() {
+        let (x, y) = cell_origin(cell);
+        let side = (cell_size - 1) as u32;
+        fill_rect(&mut image, x + 1, y + 1, side, side, LIGHT_GREEN);
+    }
+
+    let (end_x, end_y) = cell_origin(game.end());
+    let glyph_scale = cmp::max(1, (cell_size as u32 * 4 / 5) / GLYPH_HEIGHT);
+    draw_glyph(
+        &mut image,
+        &GLYPH_E,
+        (end_x + cell_size / 2, end_y + cell_size / 2),
+        glyph_scale,
+        BLACK,
+    );
+
+    let (player_x, player_y) = cell_origin(game.player());
+    let inset = cmp::max(1, cell_size / 5);
+    let player_side = cmp::max(1, cell_size - 2 * inset) as u32;
+    fill_rect(
+        &mut image,
+        player_x + inset,
+        player_y + inset,
+        player_side,
+        player_side,
+        HOT_PINK,
+    );
+
+    if game.is_won() {
+        // "WIN" across about half of the maze, on a white band.
+        let (image_w, image_h) = image.dimensions();
+        let letter_spacing = GLYPH_WIDTH + 1;
+        let text_width = 3 * letter_spacing - 1;
+        let scale = cmp::max(1, cmp::min(image_w / 2 / text_width, image_h / 2 / GLYPH_HEIGHT));
+        let band_h = (GLYPH_HEIGHT + 2) * scale;
+        let centre_y = (image_h / 2) as i32;
+        fill_rect(&mut image, 0, centre_y - (band_h / 2) as i32, image_w, band_h, WHITE);
+
+        let first_letter_x = (image_w / 2) as i32 - ((text_width - GLYPH_WIDTH) * scale / 2) as i32;
+        for (i, glyph) in [&GLYPH_W, &GLYPH_I, &GLYPH_N].iter().enumerate() {
+            let letter_x = first_letter_x + (i as u32 * letter_spacing * scale) as i32;
+            draw_glyph(&mut image, glyph, (letter_x, centre_y), scale, HOT_PINK);
+        }
+    }
+
+    image
+}
+
+pub(crate) fn maze_image_dimensions<GridIndexType, CellT, Iters>(
     grid: &Grid<GridIndexType, CellT, Iters>,
     options: &RenderOptions,
 ) -> (u32, u32)
@@ -471,22 +996,38 @@ where
 //     updated_texture.unwrap()
 // }
 
-fn colour_mul(colour: Color, scale: f32) -> Color {
-    Color {
-        r: (f32::from(colour.r) * scale) as u8,
-        g: (f32::from(colour.g) * scale) as u8,
-        b: (f32::from(colour.b) * scale) as u8,
-        a: colour.a,
-    }
+fn max_cell_weight<GridIndexType, CellT, Iters>(grid: &Grid<GridIndexType, CellT, Iters>) -> u32
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    grid.iter()
+        .filter_map(|cell| grid.cell_weight(cell))
+        .max()
+        .unwrap_or(DEFAULT_CELL_WEIGHT)
+}
+
+/// The shade of a cell that costs more than the default weight to move into, darker the closer it is to the
+/// costliest cell. `None` for any other cell.
+fn cell_weight_colour(weight: Option<u32>, max_weight: u32) -> Option<Rgb<u8>> {
+    let weight = weight.filter(|&weight| weight > DEFAULT_CELL_WEIGHT)?;
+    let intensity = (weight - DEFAULT_CELL_WEIGHT) as f32 / (max_weight - DEFAULT_CELL_WEIGHT) as f32;
+    Some(colour_mul(LAVA, 1.0 - 0.5 * intensity))
+}
+
+fn colour_mul(colour: Rgb<u8>, scale: f32) -> Rgb<u8> {
+    let Rgb([r, g, b]) = colour;
+    Rgb([
+        (f32::from(r) * scale) as u8,
+        (f32::from(g) * scale) as u8,
+        (f32::from(b) * scale) as u8,
+    ])
 }
 
 #[allow(dead_code)] // for now
-fn rainbow_colour(cycle_complete_percent: f32) -> Color {
-    let rainbow_point = match cycle_complete_percent {
-        n if n > 1.0 => 1.0,
-        n if n < 0.0 => 0.0,
-        n => n,
-    };
+fn rainbow_colour(cycle_complete_percent: f32) -> Rgb<u8> {
+    let rainbow_point = cycle_complete_percent.clamp(0.0, 1.0);
     let center = 128.0;
     let width = 127.0;
     let red_frequency = 0.7;
@@ -501,41 +1042,7 @@ fn rainbow_colour(cycle_complete_percent: f32) -> Color {
     let green = (green_frequency * i + green_phase) * width + center;
     let blue = (blue_frequency * i + blue_phase) * width + center;
 
-    Color::RGB(red as u8, green as u8, blue as u8)
-}
-
-/// Return a Rect that is centered within a parent rectangle. The rectangle will be scaled down to fit within the parent rectangle
-/// if it is bigger than the parent rectangle's width or height.
-/// `rect_width` - width of some rectangle to centre.
-/// `rect_height` - height of some rectangle to centre.
-/// `parent_rect_width` - width of the parent rectangle within which we centre a rectangle.
-/// `parent_rect_height` - height of the parent rectangle within which we centre a rectangle.
-fn centre_rectangle(rect_width: u32, rect_height: u32, parent_rect_width: u32, parent_rect_height: u32) -> Rect {
-    let rect_width_f = rect_width as f32;
-    let rect_height_f = rect_height as f32;
-    let parent_rect_width_f = parent_rect_width as f32;
-    let parent_rect_height_f = parent_rect_height as f32;
-    let parent_rect_width_i = parent_rect_width as i32;
-    let parent_rect_height_i = parent_rect_height as i32;
-
-    let width_ratio = rect_width_f / parent_rect_width_f;
-    let height_ratio = rect_height_f / parent_rect_height_f;
-
-    let (w, h) = if width_ratio > 1.0 || height_ratio > 1.0 {
-        if width_ratio > height_ratio {
-            let h = (rect_height_f / width_ratio) as i32;
-            (parent_rect_width as i32, h)
-        } else {
-            let w = (rect_width_f / height_ratio) as i32;
-            (w, parent_rect_height as i32)
-        }
-    } else {
-        (rect_width as i32, rect_height as i32)
-    };
-
-    let cx = (parent_rect_width_i - w) / 2;
-    let cy = (parent_rect_height_i - h) / 2;
-    Rect::new(cx, cy, w as u32, h as u32)
+    Rgb([red as u8, green as u8, blue as u8])
 }
 
 // Research Notes
@@ -650,3 +1157,251 @@ fn centre_rectangle(rect_width: u32, rect_height: u32, parent_rect_width: u32, p
 // accordingly.
 // Of course this only applies if you're using SDL's rendering functions,
 // not if you're e.g. using OpenGL directly.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators;
+    use crate::grids::{small_rect_grid, weave_grid};
+    use crate::units::{ColumnLength, RowLength};
+    use image::AnimationDecoder;
+    use rand::{rngs::SmallRng, SeedableRng};
+    use std::fs;
+
+    #[test]
+    fn square_grid_image_draws_walls_without_sdl() {
+        let mut g = small_rect_grid(RowLength(3), ColumnLength(2)).unwrap();
+        g.link(Cartesian2DCoordinate::new(0, 0), Cartesian2DCoordinate::new(1, 0))
+            .unwrap();
+        let options = RenderOptionsBuilder::new().cell_side_pixels_length(10).build();
+        let image = square_grid_image(&g, &options);
+
+        assert_eq!(image.dimensions(), (31, 21));
+        assert_eq!(*image.get_pixel(0, 5), BLUE);
+        assert_eq!(*image.get_pixel(5, 0), BLUE);
+        assert_eq!(*image.get_pixel(5, 10), BLUE);
+        assert_eq!(*image.get_pixel(20, 5), BLUE);
+        assert_eq!(*image.get_pixel(30, 20), BLUE);
+        // Linked cells have no wall between them
+        assert_eq!(*image.get_pixel(10, 5), WHITE);
+        assert_eq!(*image.get_pixel(5, 5), WHITE);
+    }
+
+    #[test]
+    fn square_grid_image_marks_path_and_distances() {
+        let mut g = small_rect_grid(RowLength(4), ColumnLength(4)).unwrap();
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(0));
+        let start = Cartesian2DCoordinate::new(0, 0);
+        let distances = pathing::Distances::for_grid(&g, start).unwrap();
+        let end = distances.furthest_points_on_grid()[0];
+        let path = pathing::shortest_path(&g, &distances, end);
+
+        let options = RenderOptionsBuilder::new()
+            .colour_distances(true)
+            .show_path(true)
+            .distances(Some(&distances))
+            .path(path)
+            .cell_side_pixels_length(20)
+            .build();
+        let image = square_grid_image(&g, &options);
+
+        // The start cell is the brightest green, the path runs through the cell centres.
+        assert_eq!(*image.get_pixel(3, 3), GREEN);
+        assert_eq!(*image.get_pixel(10, 10), HOT_PINK);
+    }
+
+    #[test]
+    fn square_grid_svg_has_walls_path_and_markers() {
+        let mut g = small_rect_grid(RowLength(2), ColumnLength(1)).unwrap();
+        let start = Cartesian2DCoordinate::new(0, 0);
+        let end = Cartesian2DCoordinate::new(1, 0);
+        g.link(start, end).unwrap();
+        let distances = pathing::Distances::for_grid(&g, start).unwrap();
+
+        let options = RenderOptionsBuilder::new()
+            .colour_distances(true)
+            .mark_start_end(true)
+            .start(Some(start))
+            .end(Some(end))
+            .distances(Some(&distances))
+            .path(Some(vec![start, end]))
+            .cell_side_pixels_length(10)
+            .build();
+        let svg = square_grid_svg(&g, &options);
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="21" height="11""#));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect x=").count(), 2);
+        assert!(svg.contains(r##"fill="#00ff00""##));
+        // No wall between the linked cells
+        assert!(svg.contains(r#"<path d="M0 0H10M0 0V10M0 10H10M10 0H20M20 0V10M10 10H20""#));
+        // The marked start and end hide a short path
+        assert!(!svg.contains("<polyline"));
+        assert!(svg.contains(">S</text>"));
+        assert!(svg.contains(">E</text>"));
+    }
+
+    #[test]
+    fn lines_are_clipped_to_the_image() {
+        let mut image = RgbImage::from_pixel(4, 4, WHITE);
+        draw_line(&mut image, (-2, 1), (10, 1), BLACK);
+        draw_line(&mut image, (0, 0), (3, 3), BLACK);
+
+        assert!((0..4).all(|x| *image.get_pixel(x, 1) == BLACK));
+        assert!((0..4).all(|i| *image.get_pixel(i, i) == BLACK));
+        assert_eq!(*image.get_pixel(3, 0), WHITE);
+    }
+
+    #[test]
+    fn weave_grid_image_shows_tunnel_gaps() {
+        let mut g = weave_grid(RowLength(3), ColumnLength(3)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(1, 0), gc(1, 1)).unwrap();
+        g.link(gc(1, 1), gc(1, 2)).unwrap();
+        g.link(gc(0, 1), gc(2, 1)).unwrap();
+        let options = RenderOptionsBuilder::new().cell_side_pixels_length(10).build();
+        let image = weave_grid_image(&g, &options);
+
+        assert_eq!(image.dimensions(), (31, 31));
+        // The corridor over the tunnel keeps its inset east and west walls.
+        assert_eq!(*image.get_pixel(12, 15), BLUE);
+        assert_eq!(*image.get_pixel(18, 15), BLUE);
+        // The tunnel walls show in the corridor's west margin, with the tunnel open between them.
+        assert_eq!(*image.get_pixel(11, 12), BLUE);
+        assert_eq!(*image.get_pixel(11, 18), BLUE);
+        assert_eq!(*image.get_pixel(11, 15), WHITE);
+        // The tunnel entrance leaves the western cell open to the east.
+        assert_eq!(*image.get_pixel(8, 15), WHITE);
+    }
+
+    #[test]
+    fn weave_grid_image_marks_the_path_ends() {
+        let mut g = weave_grid(RowLength(3), ColumnLength(3)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(0, 0), gc(1, 0)).unwrap();
+        let options = RenderOptionsBuilder::new()
+            .cell_side_pixels_length(10)
+            .mark_start_end(true)
+            .path(Some(vec![gc(0, 0), gc(1, 0)]))
+            .build();
+        let image = weave_grid_image(&g, &options);
+
+        // The top bar of the 'S' glyph centred in the first cell and of the 'E' glyph centred in the last.
+        assert_eq!(*image.get_pixel(4, 2), BLACK);
+        assert_eq!(*image.get_pixel(13, 2), BLACK);
+        assert_eq!(*image.get_pixel(17, 2), BLACK);
+    }
+
+    #[test]
+    fn weave_grid_image_colours_distances_through_tunnels() {
+        let mut g = weave_grid(RowLength(3), ColumnLength(3)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(1, 0), gc(1, 1)).unwrap();
+        g.link(gc(1, 1), gc(1, 2)).unwrap();
+        g.link(gc(0, 1), gc(2, 1)).unwrap();
+        g.link(gc(2, 1), gc(2, 2)).unwrap();
+        let options = RenderOptionsBuilder::new()
+            .cell_side_pixels_length(10)
+            .colour_distances(true)
+            .start(Some(gc(0, 1)))
+            .build();
+        let image = weave_grid_image(&g, &options);
+
+        // Reached through the tunnel at half of the furthest distance.
+        assert_eq!(*image.get_pixel(24, 14), colour_mul(GREEN, 0.5));
+        assert_eq!(*image.get_pixel(24, 24), BLACK);
+        // The corridor over the tunnel cannot be reached from the start.
+        assert_eq!(*image.get_pixel(14, 4), BLACK);
+    }
+
+    #[test]
+    fn animation_writes_a_frame_per_step_and_hold() {
+        let output_dir = std::env::temp_dir().join(format!("mazes_animation_test_{}", std::process::id()));
+        fs::create_dir_all(&output_dir).unwrap();
+
+        let mut g = small_rect_grid(RowLength(4), ColumnLength(3)).unwrap();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut steps = vec![];
+        generators::recursive_backtracker(
+            generators::ObservedGrid::new(&mut g, &mut |step| steps.push(step)),
+            None,
+            &mut rng,
+        );
+        let steps_count = steps.len();
+        let options = RenderOptionsBuilder::new().frames_per_step(2).hold_frames(5).build();
+
+        let gif_path = output_dir.join("maze.gif");
+        let mut replay_grid = small_rect_grid(RowLength(4), ColumnLength(3)).unwrap();
+        render_square_grid_animation(&mut replay_grid, steps.clone(), &options, &gif_path).unwrap();
+        let gif = image::codecs::gif::GifDecoder::new(File::open(&gif_path).unwrap()).unwrap();
+        let frames = gif.into_frames().collect_frames().unwrap();
+        // Each step is one GIF frame held for 2 frame durations.
+        assert_eq!(frames.len(), steps_count + 1);
+        assert_eq!(
+            frames[0].delay(),
+            Delay::from_numer_denom_ms(2 * ANIMATION_FRAME_MILLISECONDS, 1)
+        );
+
+        let png_path = output_dir.join("maze.png");
+        let mut replay_grid = small_rect_grid(RowLength(4), ColumnLength(3)).unwrap();
+        render_square_grid_animation(&mut replay_grid, steps, &options, &png_path).unwrap();
+        let png_frames_count = fs::read_dir(&output_dir)
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with("maze_")
+            })
+            .count();
+        assert_eq!(png_frames_count, 2 * steps_count + 5);
+        assert_eq!(replay_grid.links_count(), g.links_count());
+
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+
+    #[test]
+    fn play_frame_shows_player_trail_and_win_banner() {
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let mut g = small_rect_grid(RowLength(3), ColumnLength(1)).unwrap();
+        g.link(gc(0, 0), gc(1, 0)).unwrap();
+        g.link(gc(1, 0), gc(2, 0)).unwrap();
+        let options = RenderOptionsBuilder::new().cell_side_pixels_length(20).build();
+        let mut game = MazeGame::new(gc(0, 0), gc(2, 0));
+
+        game.move_player(&g, CompassPrimary::East);
+        let image = play_frame_image(&g, &game, &options);
+        assert_eq!(*image.get_pixel(10, 10), LIGHT_GREEN);
+        assert_eq!(*image.get_pixel(30, 10), HOT_PINK);
+        assert_eq!(*image.get_pixel(0, 10), BLUE);
+
+        game.move_player(&g, CompassPrimary::East);
+        assert!(game.is_won());
+        // The win banner is drawn across the middle of the maze, over the west wall.
+        let image = play_frame_image(&g, &game, &options);
+        assert_eq!(*image.get_pixel(0, 10), WHITE);
+    }
+
+    #[test]
+    fn shade_weights_darkens_costlier_cells() {
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let mut g = small_rect_grid(RowLength(3), ColumnLength(1)).unwrap();
+        g.link_all_neighbours().unwrap();
+        g.set_cell_weight(gc(1, 0), 3);
+        g.set_cell_weight(gc(2, 0), 5);
+        let options = RenderOptionsBuilder::new().shade_weights(true).build();
+
+        let image = square_grid_image(&g, &options);
+        assert_eq!(*image.get_pixel(5, 5), WHITE);
+        assert_eq!(*image.get_pixel(15, 5), colour_mul(LAVA, 0.75));
+        assert_eq!(*image.get_pixel(25, 5), colour_mul(LAVA, 0.5));
+
+        let svg = square_grid_svg(&g, &options);
+        assert!(svg.contains(r##"fill="#bf6900""##));
+        assert!(svg.contains(r##"fill="#7f4600""##));
+        let unshaded_svg = square_grid_svg(&g, &RenderOptionsBuilder::new().build());
+        assert!(!unshaded_svg.contains("#7f4600"));
+    }
+}
@@ -1,23 +1,252 @@
-use sdl2::image::InitFlag;
+//! Showing mazes in an SDL window. Only built with the `sdl` feature, everything else renders without SDL.
+
+use crate::{
+    cells::{CompassPrimary, SquareCell},
+    grid::{Grid, IndexType},
+    grid_traits::GridIterators,
+    play::MazeGame,
+    renderers::{maze_image_dimensions, play_frame_image, RenderOptions, WHITE},
+};
+
+use image::RgbImage;
+use sdl2::{
+    event::{Event, WindowEvent},
+    hint,
+    keyboard::Keycode,
+    pixels::{Color, PixelFormatEnum},
+    rect::Rect,
+    render::{Canvas, Texture, TextureCreator},
+    surface::Surface,
+    video::{Window, WindowContext},
+};
+use std::cmp;
 
 pub struct SdlSetup {
     pub sdl_context: sdl2::Sdl,
     pub video_subsystem: sdl2::VideoSubsystem,
-    pub timer_subsystem: sdl2::TimerSubsystem,
-    pub ttf_context: sdl2::ttf::Sdl2TtfContext,
 }
 
 pub fn init() -> SdlSetup {
     let sdl_context: sdl2::Sdl = sdl2::init().unwrap();
     let video_subsystem: sdl2::VideoSubsystem = sdl_context.video().unwrap();
-    let timer_subsystem = sdl_context.timer().unwrap();
-    let ttf_context = sdl2::ttf::init().expect("Failed to init true type fonts");
-    sdl2::image::init(InitFlag::PNG | InitFlag::JPG).unwrap();
 
     SdlSetup {
         sdl_context,
         video_subsystem,
-        timer_subsystem,
-        ttf_context,
     }
 }
+
+/// Show the maze image in a window until it is closed or Q is pressed.
+pub(crate) fn show_maze_on_screen(maze_image: RgbImage, sdl_setup: &SdlSetup) {
+    let (maze_w, maze_h) = maze_image.dimensions();
+    let (mut canvas, window_w, window_h) = maze_window_canvas(maze_w, maze_h, sdl_setup);
+    let texture_creator = canvas.texture_creator();
+
+    let maze_texture = image_texture(maze_image, &texture_creator);
+    let mut maze_target_rect = centre_rectangle(maze_w, maze_h, window_w, window_h);
+
+    let mut events = sdl_setup.sdl_context.event_pump().unwrap();
+    'running: loop {
+        for event in events.poll_iter() {
+            match event {
+                Event::Quit { .. }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::Q),
+                    ..
+                } => break 'running,
+                Event::Window {
+                    win_event: WindowEvent::Resized(new_width, new_height),
+                    ..
+                } => {
+                    maze_target_rect = centre_rectangle(maze_w, maze_h, new_width as u32, new_height as u32);
+                }
+                _ => continue,
+                // todo allow resolution > display size?
+                // todo allow control of max on screen window size
+            }
+        }
+
+        canvas.set_draw_color(Color::RGB(WHITE[0], WHITE[1], WHITE[2]));
+        canvas.clear();
+        canvas
+            .copy(&maze_texture, None, Some(maze_target_rect))
+            .expect("Maze texture copy failed.");
+        canvas.present();
+    }
+}
+
+/// Play the maze in a window, moving from the start point to the end point with the arrow keys or WASD.
+///
+/// The player is drawn in hot pink and leaves a light green trail through the cells they have been in. The window
+/// title shows the move count and time taken, then the result once the end point is reached.
+/// Press R to play again or Q to quit.
+///
+/// # Panics
+/// When the render options do not have a start and an end point.
+pub fn play_square_grid<GridIndexType, Iters>(grid: &Grid<GridIndexType, SquareCell, Iters>, options: &RenderOptions)
+where
+    GridIndexType: IndexType,
+    Iters: GridIterators<SquareCell>,
+{
+    let start = options.start.expect("Playing a maze needs a start point.");
+    let end = options.end.expect("Playing a maze needs an end point.");
+    let mut game = MazeGame::new(start, end);
+
+    let sdl_setup = init();
+    let (maze_w, maze_h) = maze_image_dimensions(grid, options);
+    let (mut canvas, window_w, window_h) = maze_window_canvas(maze_w, maze_h, &sdl_setup);
+    let texture_creator = canvas.texture_creator();
+
+    let mut maze_texture = image_texture(play_frame_image(grid, &game, options), &texture_creator);
+    let mut maze_target_rect = centre_rectangle(maze_w, maze_h, window_w, window_h);
+    let mut title = String::new();
+
+    let mut events = sdl_setup.sdl_context.event_pump().unwrap();
+    'running: loop {
+        let mut game_changed = false;
+        for event in events.poll_iter() {
+            match event {
+                Event::Quit { .. }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::Q),
+                    ..
+                } => break 'running,
+                Event::KeyDown {
+                    keycode: Some(Keycode::R),
+                    ..
+                } => {
+                    game.restart();
+                    game_changed = true;
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode), ..
+                } => {
+                    let direction = match keycode {
+                        Keycode::Up | Keycode::W => Some(CompassPrimary::North),
+                        Keycode::Down | Keycode::S => Some(CompassPrimary::South),
+                        Keycode::Left | Keycode::A => Some(CompassPrimary::West),
+                        Keycode::Right | Keycode::D => Some(CompassPrimary::East),
+                        _ => None,
+                    };
+                    if let Some(direction) = direction {
+                        game_changed |= game.move_player(grid, direction);
+                    }
+                }
+                Event::Window {
+                    win_event: WindowEvent::Resized(new_width, new_height),
+                    ..
+                } => {
+                    maze_target_rect = centre_rectangle(maze_w, maze_h, new_width as u32, new_height as u32);
+                }
+                _ => continue,
+            }
+        }
+
+        if game_changed {
+            maze_texture = image_texture(play_frame_image(grid, &game, options), &texture_creator);
+        }
+
+        let seconds = game.elapsed().as_secs_f32();
+        let new_title = if game.is_won() {
+            format!(
+                "Mazes - Solved in {} moves, {:.1}s. R to play again, Q to quit.",
+                game.moves(),
+                seconds
+            )
+        } else {
+            format!("Mazes - {} moves, {:.1}s", game.moves(), seconds)
+        };
+        if new_title != title {
+            canvas
+                .window_mut()
+                .set_title(&new_title)
+                .expect("Window title update failed.");
+            title = new_title;
+        }
+
+        canvas.set_draw_color(Color::RGB(WHITE[0], WHITE[1], WHITE[2]));
+        canvas.clear();
+        canvas
+            .copy(&maze_texture, None, Some(maze_target_rect))
+            .expect("Maze texture copy failed.");
+        canvas.present();
+    }
+}
+
+/// Create a window that fits the maze image, unless the image is bigger than the display resolution.
+/// Returns the window's canvas and its size.
+fn maze_window_canvas(maze_w: u32, maze_h: u32, sdl_setup: &SdlSetup) -> (Canvas<Window>, u32, u32) {
+    // 0 or 'nearest' == nearest pixel sampling
+    // 1 or 'linear' == linear filtering (supported by OpenGL and Direct3D)
+    // 2 or 'best' == anisotropic filtering (supported by Direct3D)
+    // The hint strings don't seem to be abstracted in the rust source at the moment but we can see
+    // the #defines at e.g. https://github.com/spurious/SDL-mirror/blob/master/include/SDL_hints.h
+    // SDL_HINT_RENDER_SCALE_QUALITY applies per texture, not per renderer.
+    hint::set("SDL_RENDER_SCALE_QUALITY", "1");
+
+    let primary_display_mode = sdl_setup.video_subsystem.current_display_mode(0).unwrap();
+    let (display_w, display_h) = (primary_display_mode.w as u32, primary_display_mode.h as u32);
+    let maze_image_padding = 32;
+    let window_w = cmp::min(display_w, maze_w + maze_image_padding);
+    let window_h = cmp::min(display_h, maze_h + maze_image_padding);
+
+    let mut window_builder = sdl_setup.video_subsystem.window("Mazes", window_w, window_h);
+    let window = window_builder
+        .position_centered()
+        .resizable()
+        .allow_highdpi()
+        .build()
+        .unwrap();
+    let canvas = window
+        .into_canvas()
+        .present_vsync()
+        .accelerated()
+        .target_texture()
+        .build()
+        .unwrap();
+
+    (canvas, window_w, window_h)
+}
+
+fn image_texture(image: RgbImage, texture_creator: &TextureCreator<WindowContext>) -> Texture<'_> {
+    // Wrap the image pixels in a surface so they can be uploaded to a texture.
+    let (image_w, image_h) = image.dimensions();
+    let mut pixels = image.into_raw();
+    let surface = Surface::from_data(&mut pixels, image_w, image_h, image_w * 3, PixelFormatEnum::RGB24)
+        .expect("Surface creation failed.");
+    texture_creator.create_texture_from_surface(surface).unwrap()
+}
+
+/// Return a Rect that is centered within a parent rectangle. The rectangle will be scaled down to fit within the parent rectangle
+/// if it is bigger than the parent rectangle's width or height.
+/// `rect_width` - width of some rectangle to centre.
+/// `rect_height` - height of some rectangle to centre.
+/// `parent_rect_width` - width of the parent rectangle within which we centre a rectangle.
+/// `parent_rect_height` - height of the parent rectangle within which we centre a rectangle.
+fn centre_rectangle(rect_width: u32, rect_height: u32, parent_rect_width: u32, parent_rect_height: u32) -> Rect {
+    let rect_width_f = rect_width as f32;
+    let rect_height_f = rect_height as f32;
+    let parent_rect_width_f = parent_rect_width as f32;
+    let parent_rect_height_f = parent_rect_height as f32;
+    let parent_rect_width_i = parent_rect_width as i32;
+    let parent_rect_height_i = parent_rect_height as i32;
+
+    let width_ratio = rect_width_f / parent_rect_width_f;
+    let height_ratio = rect_height_f / parent_rect_height_f;
+
+    let (w, h) = if width_ratio > 1.0 || height_ratio > 1.0 {
+        if width_ratio > height_ratio {
+            let h = (rect_height_f / width_ratio) as i32;
+            (parent_rect_width as i32, h)
+        } else {
+            let w = (rect_width_f / height_ratio) as i32;
+            (w, parent_rect_height as i32)
+        }
+    } else {
+        (rect_width as i32, rect_height as i32)
+    };
+
+    let cx = (parent_rect_width_i - w) / 2;
+    let cy = (parent_rect_height_i - h) / 2;
+    Rect::new(cx, cy, w as u32, h as u32)
+}
@@ -0,0 +1,1383 @@
+use crate::{
+    cells::{Cartesian2DCoordinate, Cell, Coordinate, HexCell, PolarCell, SquareCell, TriangleCell, WeaveCell},
+    grid::{CellLinkError, Grid, IndexType, DEFAULT_CELL_WEIGHT, DEFAULT_LINK_WEIGHT},
+    grid_coordinates::{PolarGridCoordinates, RectGridCoordinates},
+    grid_dimensions::{PolarGridDimensions, RectGridDimensions},
+    grid_iterators::{PolarGridIterators, RectGridIterators},
+    grid_traits::{GridCoordinates, GridDimensions, GridIterators},
+    pathing::{Distances, MaxDistance},
+    units::{ColumnLength, RowLength, RowsCount},
+    utils::FnvHashSet,
+};
+
+use image::{DynamicImage, Rgb, RgbImage};
+use serde::{de::Error as _, Deserializer, Serializer};
+use serde_derive::{Deserialize, Serialize};
+use std::{error, fmt, fmt::Write, rc::Rc};
+
+/// Marks JSON saved by `Grid`'s `Serialize` implementation as a maze grid.
+pub const GRID_FORMAT: &str = "mazes-grid";
+/// The version of the saved grid format, increased whenever the format changes in a way older readers cannot load.
+pub const GRID_FORMAT_VERSION: u32 = 1;
+
+/// Problems reading a maze in the edges format, see `read_edges`.
+/// Line numbers start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgesFormatError {
+    MissingHeader,
+    /// A line that is not two whole numbers.
+    MalformedLine {
+        line: usize,
+    },
+    /// The number of vertices in the header is not the number of cells in the grid.
+    VertexCountMismatch {
+        grid_cells: usize,
+        vertices: usize,
+    },
+    /// The number of edges in the header is not the number of edge lines.
+    EdgeCountMismatch {
+        header_edges: usize,
+        edges: usize,
+    },
+    /// A vertex number that is 0 or more than the number of vertices.
+    VertexOutOfRange {
+        line: usize,
+        vertex: usize,
+    },
+    /// An edge between two cells that are not next to each other on the grid.
+    NotNeighbours {
+        line: usize,
+        a: usize,
+        b: usize,
+    },
+    /// An edge between two cells that an earlier line already joined, in either order.
+    DuplicateEdge {
+        line: usize,
+        a: usize,
+        b: usize,
+    },
+    /// More edges than the grid index type can count, e.g. on a large `small_rect_grid`.
+    TooManyEdges {
+        edges: usize,
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for EdgesFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EdgesFormatError::MissingHeader => write!(f, "missing the 'vertices edges' count line"),
+            EdgesFormatError::MalformedLine { line } => write!(f, "line {} is not a pair of numbers", line),
+            EdgesFormatError::VertexCountMismatch { grid_cells, vertices } => {
+                write!(f, "{} vertices do not match the grid's {} cells", vertices, grid_cells)
+            }
+            EdgesFormatError::EdgeCountMismatch { header_edges, edges } => {
+                write!(f, "expected {} edges, found {}", header_edges, edges)
+            }
+            EdgesFormatError::VertexOutOfRange { line, vertex } => {
+                write!(f, "line {} has vertex {} which is not on the grid", line, vertex)
+            }
+            EdgesFormatError::NotNeighbours { line, a, b } => {
+                write!(
+                    f,
+                    "line {} joins vertices {} and {} which are not neighbours",
+                    line, a, b
+                )
+            }
+            EdgesFormatError::DuplicateEdge { line, a, b } => {
+                write!(
+                    f,
+                    "line {} joins vertices {} and {} which are already joined",
+                    line, a, b
+                )
+            }
+            EdgesFormatError::TooManyEdges { edges, capacity } => {
+                write!(
+                    f,
+                    "{} edges are more than the grid can link, at most {}",
+                    edges, capacity
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for EdgesFormatError {}
+
+/// Problems reading a maze drawn as text, see `read_text_maze`.
+/// Line and column numbers start at 1 and count characters, not bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextMazeError {
+    /// There is no maze in the text.
+    Empty,
+    /// Each row of cells takes two lines plus the line for the north wall, so there must be an odd number of lines.
+    LineCount { lines: usize },
+    /// Each cell is four characters wide plus the character for the west wall.
+    LineWidth { line: usize, width: usize },
+    /// A line that is a different width to the first line.
+    UnevenLine {
+        line: usize,
+        width: usize,
+        expected_width: usize,
+    },
+    /// A wall that is neither a wall nor a passage.
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    /// A passage through the outside wall of the maze.
+    OpenBoundary { line: usize, column: usize },
+    /// The maze has too many cells for the grid index type.
+    TooLarge { width: usize, height: usize },
+}
+
+impl fmt::Display for TextMazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TextMazeError::Empty => write!(f, "there is no maze in the text"),
+            TextMazeError::LineCount { lines } => {
+                write!(f, "{} lines is not a maze, a maze of n rows has 2n + 1 lines", lines)
+            }
+            TextMazeError::LineWidth { line, width } => write!(
+                f,
+                "line {} is {} characters wide, a maze of n columns is 4n + 1 characters wide",
+                line, width
+            ),
+            TextMazeError::UnevenLine {
+                line,
+                width,
+                expected_width,
+            } => write!(
+                f,
+                "line {} is {} characters wide, expected {} like the first line",
+                line, width, expected_width
+            ),
+            TextMazeError::UnexpectedCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "line {} column {} has '{}' which is neither a wall nor a passage",
+                line, column, character
+            ),
+            TextMazeError::OpenBoundary { line, column } => {
+                write!(f, "line {} column {} is a gap in the outside wall", line, column)
+            }
+            TextMazeError::TooLarge { width, height } => {
+                write!(f, "a {}x{} maze has too many cells for the grid", width, height)
+            }
+        }
+    }
+}
+
+impl error::Error for TextMazeError {}
+
+/// Problems reading a maze from an image, see `read_image_maze`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageMazeError {
+    /// No cell size was given and it could not be worked out from the walls in the image.
+    UnknownCellSize,
+    /// Cells must be at least 3 pixels wide to tell the walls from the passages.
+    CellTooSmall { cell_pixels: u32 },
+    /// The image is smaller than one cell.
+    NoCells { image_width: u32, image_height: u32 },
+    /// The maze has too many cells for the grid index type.
+    TooLarge { width: usize, height: usize },
+}
+
+impl fmt::Display for ImageMazeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ImageMazeError::UnknownCellSize => write!(f, "cannot work out the cell size from the image's walls"),
+            ImageMazeError::CellTooSmall { cell_pixels } => {
+                write!(
+                    f,
+                    "{} pixel cells are too small, cells need at least 3 pixels",
+                    cell_pixels
+                )
+            }
+            ImageMazeError::NoCells {
+                image_width,
+                image_height,
+            } => write!(
+                f,
+                "a {}x{} pixel image is too small for a cell",
+                image_width, image_height
+            ),
+            ImageMazeError::TooLarge { width, height } => {
+                write!(f, "a {}x{} maze has too many cells for the grid", width, height)
+            }
+        }
+    }
+}
+
+impl error::Error for ImageMazeError {}
+
+/// Write the maze as text in the edges format.
+///
+/// Line 1 is the number of vertices (grid cells) and the number of edges (links): `n m`.
+/// Each following line is an edge between two vertices: `a b`. Vertices are the 1-based row major index of a cell.
+pub fn write_edges<GridIndexType, CellT, Iters>(grid: &Grid<GridIndexType, CellT, Iters>) -> String
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    // Writing to a String cannot fail, so the fmt::Results are ignored.
+    let mut edges = String::new();
+    let _ = writeln!(edges, "{} {}", grid.size(), grid.links_count());
+
+    for (src, dst) in grid.iter_links() {
+        let index_a = grid
+            .grid_coordinate_to_index(src)
+            .expect("Links iter should give valid coordinate");
+        let index_b = grid
+            .grid_coordinate_to_index(dst)
+            .expect("Links iter should give valid coordinate");
+        let _ = writeln!(edges, "{} {}", index_a + 1, index_b + 1);
+    }
+
+    edges
+}
+
+/// Link the cells of the grid from a maze written in the edges format by `write_edges`.
+///
+/// The grid must have the same shape as the grid the maze was written from, as only the number of cells is
+/// recorded, and it is usually a new grid without any links. The grid is left unchanged if the text is not a maze
+/// that fits it.
+pub fn read_edges<GridIndexType, CellT, Iters>(
+    grid: &mut Grid<GridIndexType, CellT, Iters>,
+    edges_text: &str,
+) -> Result<(), EdgesFormatError>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    let mut lines = edges_text
+        .lines()
+        .enumerate()
+        .map(|(index, text)| (index + 1, text))
+        .filter(|(_, text)| !text.trim().is_empty());
+
+    let (header_line, header_text) = lines.next().ok_or(EdgesFormatError::MissingHeader)?;
+    let (vertices, header_edges) = parse_number_pair(header_text, header_line)?;
+    if vertices != grid.size() {
+        return Err(EdgesFormatError::VertexCountMismatch {
+            grid_cells: grid.size(),
+            vertices,
+        });
+    }
+
+    // The header's edge count is not trusted to size anything until it matches the edges read.
+    let mut links = Vec::new();
+    let mut linked_vertices: FnvHashSet<(usize, usize)> = FnvHashSet::default();
+    for (line, text) in lines {
+        let (a, b) = parse_number_pair(text, line)?;
+        let cell = |vertex: usize| {
+            (1..=vertices)
+                .contains(&vertex)
+                .then(|| CellT::Coord::from_row_major_index(vertex - 1, grid.dimensions()))
+                .ok_or(EdgesFormatError::VertexOutOfRange { line, vertex })
+        };
+        let (cell_a, cell_b) = (cell(a)?, cell(b)?);
+        if !linked_vertices.insert((a.min(b), a.max(b))) {
+            return Err(EdgesFormatError::DuplicateEdge { line, a, b });
+        }
+        links.push(((line, a, b), cell_a, cell_b));
+    }
+    if links.len() != header_edges {
+        return Err(EdgesFormatError::EdgeCountMismatch {
+            header_edges,
+            edges: links.len(),
+        });
+    }
+    // Links the grid already has count against its capacity, even if some of them are among the edges.
+    let capacity = grid.links_capacity().saturating_sub(grid.links_count());
+    if links.len() > capacity {
+        return Err(EdgesFormatError::TooManyEdges {
+            edges: links.len(),
+            capacity,
+        });
+    }
+
+    link_neighbour_pairs(grid, &links).map_err(|error| match error {
+        NeighbourLinkError::NotNeighbours((line, a, b)) => EdgesFormatError::NotNeighbours { line, a, b },
+        NeighbourLinkError::Link(_, link_error) => panic!("Checked vertices failed to link: {:?}", link_error),
+    })
+}
+
+/// Why a pair of cells, tagged with where the pair came from, could not be linked by `link_neighbour_pairs`.
+#[derive(Debug)]
+enum NeighbourLinkError<Tag> {
+    NotNeighbours(Tag),
+    Link(Tag, CellLinkError),
+}
+
+/// Link each pair of cells, checking that they are neighbours. The grid is left unchanged if any pair fails.
+///
+/// Weaving cells can also be linked to the cell beyond a neighbour, tunnelling under it, which `Grid::neighbours`
+/// only allows once the corridor tunnelled under is in place. So the pairs of adjacent cells are linked first, then
+/// the tunnels are retried until no more of them can be linked, as one tunnel can open up the corridor for another.
+fn link_neighbour_pairs<GridIndexType, CellT, Iters, Tag>(
+    grid: &mut Grid<GridIndexType, CellT, Iters>,
+    pairs: &[(Tag, CellT::Coord, CellT::Coord)],
+) -> Result<(), NeighbourLinkError<Tag>>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    Tag: Copy,
+{
+    let mut new_links: Vec<(CellT::Coord, CellT::Coord)> = Vec::new();
+    let result = link_neighbour_pairs_recording(grid, pairs, &mut new_links);
+    if result.is_err() {
+        for (a, b) in new_links {
+            let _ = grid.unlink(a, b);
+        }
+    }
+    result
+}
+
+fn link_neighbour_pairs_recording<GridIndexType, CellT, Iters, Tag>(
+    grid: &mut Grid<GridIndexType, CellT, Iters>,
+    pairs: &[(Tag, CellT::Coord, CellT::Coord)],
+    new_links: &mut Vec<(CellT::Coord, CellT::Coord)>,
+) -> Result<(), NeighbourLinkError<Tag>>
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    Tag: Copy,
+{
+    let mut link = |grid: &mut Grid<GridIndexType, CellT, Iters>, (tag, a, b): (Tag, CellT::Coord, CellT::Coord)| {
+        if !grid.is_linked(a, b) {
+            grid.link(a, b).map_err(|error| NeighbourLinkError::Link(tag, error))?;
+            new_links.push((a, b));
+        }
+        Ok(())
+    };
+
+    let mut tunnels = Vec::new();
+    for &(tag, a, b) in pairs {
+        if !grid.is_valid_coordinate(a) || !grid.is_valid_coordinate(b) {
+            return Err(NeighbourLinkError::Link(tag, CellLinkError::InvalidGridCoordinate));
+        }
+        let is_adjacent = CellT::offset_directions(Some(a), grid.dimensions())
+            .iter()
+            .any(|dir| grid.neighbour_at_direction(a, *dir) == Some(b));
+        if is_adjacent && grid.neighbours(a).contains(&b) {
+            link(grid, (tag, a, b))?;
+        } else {
+            tunnels.push((tag, a, b));
+        }
+    }
+
+    while !tunnels.is_empty() {
+        let tunnels_count = tunnels.len();
+        let mut untunnelled = Vec::new();
+        for (tag, a, b) in tunnels {
+            if grid.neighbours(a).contains(&b) {
+                link(grid, (tag, a, b))?;
+            } else {
+                untunnelled.push((tag, a, b));
+            }
+        }
+        if untunnelled.len() == tunnels_count {
+            return Err(NeighbourLinkError::NotNeighbours(untunnelled[0].0));
+        }
+        tunnels = untunnelled;
+    }
+    Ok(())
+}
+
+fn parse_number_pair(text: &str, line: usize) -> Result<(usize, usize), EdgesFormatError> {
+    let mut numbers = text.split_whitespace().map(str::parse::<usize>);
+    match (numbers.next(), numbers.next(), numbers.next()) {
+        (Some(Ok(first)), Some(Ok(second)), None) => Ok((first, second)),
+        _ => Err(EdgesFormatError::MalformedLine { line }),
+    }
+}
+
+/// Read a maze drawn as text into a new rectangular grid, linking the cells that have a passage between them.
+///
+/// The text can be the box drawing characters of `Grid`'s `Display` implementation, or plain ASCII:
+///
+/// ```text
+/// +---+---+
+/// |       |
+/// +---+   +
+/// |       |
+/// +---+---+
+/// ```
+///
+/// Only the walls between cells are read. Corners and whatever is inside the cells, e.g. distances or a path, are
+/// ignored. Blank lines before and after the maze are skipped.
+pub fn read_text_maze<GridIndexType: IndexType>(
+    maze_text: &str,
+) -> Result<Grid<GridIndexType, SquareCell, RectGridIterators>, TextMazeError> {
+    let all_lines = maze_text
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .collect::<Vec<_>>();
+    let first_line = all_lines
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .ok_or(TextMazeError::Empty)?;
+    let last_line = all_lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .ok_or(TextMazeError::Empty)?;
+    // (line number, characters)
+    let lines = all_lines[first_line..=last_line]
+        .iter()
+        .enumerate()
+        .map(|(index, line)| (first_line + index + 1, line.chars().collect::<Vec<char>>()))
+        .collect::<Vec<_>>();
+
+    if lines.len() < 3 || lines.len() % 2 == 0 {
+        return Err(TextMazeError::LineCount { lines: lines.len() });
+    }
+    let (first_line_number, ref first_line_chars) = lines[0];
+    let expected_width = first_line_chars.len();
+    if expected_width < 5 || expected_width % 4 != 1 {
+        return Err(TextMazeError::LineWidth {
+            line: first_line_number,
+            width: expected_width,
+        });
+    }
+    if let Some((line, chars)) = lines.iter().find(|(_, chars)| chars.len() != expected_width) {
+        return Err(TextMazeError::UnevenLine {
+            line: *line,
+            width: chars.len(),
+            expected_width,
+        });
+    }
+
+    let (width, height) = ((expected_width - 1) / 4, lines.len() / 2);
+    let mut grid = SquareCell::new_grid::<GridIndexType>(SavedDimensions::Rectangular { width, height })
+        .ok_or(TextMazeError::TooLarge { width, height })?;
+
+    // Is the wall at the line and column (0 based) open? Walls on the outside of the maze must be closed.
+    let is_open = |(line, ref chars): &(usize, Vec<char>), column: usize, horizontal: bool, boundary: bool| {
+        let wall_chars = if horizontal {
+            &chars[column..column + 3]
+        } else {
+            &chars[column..=column]
+        };
+        if wall_chars.iter().all(|&c| is_wall_character(c, horizontal)) {
+            Ok(false)
+        } else if wall_chars.iter().all(|&c| c == ' ') {
+            if boundary {
+                Err(TextMazeError::OpenBoundary {
+                    line: *line,
+                    column: column + 1,
+                })
+            } else {
+                Ok(true)
+            }
+        } else {
+            // Either a character that is not part of a wall, or a wall with a gap in it.
+            let (offset, &character) = wall_chars
+                .iter()
+                .enumerate()
+                .find(|(_, &c)| c != ' ' && !is_wall_character(c, horizontal))
+                .or_else(|| wall_chars.iter().enumerate().find(|(_, &c)| c == ' '))
+                .expect("A wall that is not all wall or all passage has another character");
+            Err(TextMazeError::UnexpectedCharacter {
+                line: *line,
+                column: column + offset + 1,
+                character,
+            })
+        }
+    };
+
+    for x in 0..width {
+        is_open(&lines[0], 4 * x + 1, true, true)?;
+    }
+    let mut links = vec![];
+    for y in 0..height {
+        let (middle, bottom) = (&lines[2 * y + 1], &lines[2 * y + 2]);
+        is_open(middle, 0, false, true)?;
+        for x in 0..width {
+            let cell = Cartesian2DCoordinate::new(x as u32, y as u32);
+            if is_open(middle, 4 * x + 4, false, x == width - 1)? {
+                links.push((cell, Cartesian2DCoordinate::new(x as u32 + 1, y as u32)));
+            }
+            if is_open(bottom, 4 * x + 1, true, y == height - 1)? {
+                links.push((cell, Cartesian2DCoordinate::new(x as u32, y as u32 + 1)));
+            }
+        }
+    }
+
+    for (a, b) in links {
+        grid.link(a, b).expect("Neighbouring cells on the grid are linkable");
+    }
+    Ok(grid)
+}
+
+fn is_wall_character(c: char, horizontal: bool) -> bool {
+    if horizontal {
+        c == '─' || c == '-'
+    } else {
+        c == '│' || c == '|'
+    }
+}
+
+/// Read a maze from an image of it into a new rectangular grid, linking the cells that have no wall between them.
+///
+/// The image can be a PNG drawn by `render_square_grid`, or e.g. a scan cropped to the maze with `cell_pixels` wide
+/// cells. The top left pixel must be on the maze's outside wall, as the walls are taken to be the colour of that
+/// pixel. Walls are looked for where cells meet, at a quarter, half and three quarters of the way along, and a little
+/// either side to allow for thick or slightly misplaced walls, so paths, distance colours and the start and end marks
+/// drawn inside the cells are ignored.
+///
+/// Without `cell_pixels` the cell size is worked out from the walls, which only works for rendered images where the
+/// walls lie exactly on the grid of cells.
+pub fn read_image_maze<GridIndexType: IndexType>(
+    maze_image: &DynamicImage,
+    cell_pixels: Option<u32>,
+) -> Result<Grid<GridIndexType, SquareCell, RectGridIterators>, ImageMazeError> {
+    let image = maze_image.to_rgb8();
+    let (image_width, image_height) = image.dimensions();
+    let no_cells = ImageMazeError::NoCells {
+        image_width,
+        image_height,
+    };
+    if image_width == 0 || image_height == 0 {
+        return Err(no_cells);
+    }
+    let wall_colour = *image.get_pixel(0, 0);
+
+    let cell_pixels = match cell_pixels {
+        Some(cell_pixels) => cell_pixels,
+        None => detect_cell_pixels(&image, wall_colour).ok_or(ImageMazeError::UnknownCellSize)?,
+    };
+    if cell_pixels < 3 {
+        return Err(ImageMazeError::CellTooSmall { cell_pixels });
+    }
+    // Rendered images have an extra pixel for the south and east walls, scans may not.
+    let cells_across = |pixels: u32| (f64::from(pixels) / f64::from(cell_pixels)).round() as usize;
+    let (width, height) = (cells_across(image_width), cells_across(image_height));
+    if width == 0 || height == 0 {
+        return Err(no_cells);
+    }
+    let mut grid = SquareCell::new_grid::<GridIndexType>(SavedDimensions::Rectangular { width, height })
+        .ok_or(ImageMazeError::TooLarge { width, height })?;
+
+    let is_wall_pixel =
+        |x: u32, y: u32| x < image_width && y < image_height && is_similar_colour(*image.get_pixel(x, y), wall_colour);
+    let wall_reach = cell_pixels / 8;
+    // Is there a wall along the grid line at `line` pixels across, from `start` to `start + cell_pixels`?
+    let has_wall = |line: u32, start: u32, vertical: bool| {
+        let samples_on_wall = [1, 2, 3]
+            .iter()
+            .filter(|&&quarter| {
+                let along = start + quarter * cell_pixels / 4;
+                (line.saturating_sub(wall_reach)..=line + wall_reach).any(|across| {
+                    if vertical {
+                        is_wall_pixel(across, along)
+                    } else {
+                        is_wall_pixel(along, across)
+                    }
+                })
+            })
+            .count();
+        samples_on_wall >= 2
+    };
+
+    let mut links = vec![];
+    for y in 0..height as u32 {
+        for x in 0..width as u32 {
+            let cell = Cartesian2DCoordinate::new(x, y);
+            if x + 1 < width as u32 && !has_wall((x + 1) * cell_pixels, y * cell_pixels, true) {
+                links.push((cell, Cartesian2DCoordinate::new(x + 1, y)));
+            }
+            if y + 1 < height as u32 && !has_wall((y + 1) * cell_pixels, x * cell_pixels, false) {
+                links.push((cell, Cartesian2DCoordinate::new(x, y + 1)));
+            }
+        }
+    }
+
+    for (a, b) in links {
+        grid.link(a, b).expect("Neighbouring cells on the grid are linkable");
+    }
+    Ok(grid)
+}
+
+/// Close enough in colour to be the same ink, allowing for scanning and compression noise.
+fn is_similar_colour(a: Rgb<u8>, b: Rgb<u8>) -> bool {
+    a.0.iter().zip(b.0.iter()).all(|(&a, &b)| a.abs_diff(b) <= 64)
+}
+
+/// The cell size of an image drawn by `render_square_grid`: the largest size for which every wall pixel is on the
+/// grid of cells. Rendered images are one pixel wider and higher than a whole number of cells.
+fn detect_cell_pixels(image: &RgbImage, wall_colour: Rgb<u8>) -> Option<u32> {
+    let (image_width, image_height) = image.dimensions();
+    let (mut a, mut b) = (image_width.checked_sub(1)?, image_height.checked_sub(1)?);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    let common_size = a;
+
+    (3..=common_size)
+        .rev()
+        .filter(|cell_pixels| common_size % cell_pixels == 0)
+        .find(|&cell_pixels| {
+            image.enumerate_pixels().all(|(x, y, &colour)| {
+                x % cell_pixels == 0 || y % cell_pixels == 0 || !is_similar_colour(colour, wall_colour)
+            })
+        })
+}
+
+/// Write the maze graph in the Graphviz DOT format.
+///
+/// Nodes are the row major indices of the cells, positioned (`pos`) by their 2d coordinates with y negated so that the
+/// first row is at the top, which the `neato` and `fdp` layouts keep. Nodes are labelled with their distance from
+/// the start if `distances` are given.
+pub fn write_dot<GridIndexType, CellT, Iters, MaxDistanceT>(
+    grid: &Grid<GridIndexType, CellT, Iters>,
+    distances: Option<&Distances<CellT, MaxDistanceT>>,
+) -> String
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    MaxDistanceT: MaxDistance,
+{
+    let mut dot = String::new();
+    let _ = writeln!(dot, "graph maze {{");
+    let node_shape = if distances.is_some() { "circle" } else { "point" };
+    let _ = writeln!(dot, "    node [shape={}];", node_shape);
+
+    for cell in grid.iter() {
+        let index = grid
+            .grid_coordinate_to_index(cell)
+            .expect("Cell iter should give valid coordinate");
+        let c2d = cell.as_cartesian_2d();
+        let _ = write!(dot, "    {} [pos=\"{},{}!\"", index, c2d.x, -i64::from(c2d.y));
+        if let Some(distance) = distances.and_then(|d| d.distance_from_start_to(cell)) {
+            let _ = write!(dot, ", label=\"{}\"", distance);
+        }
+        let _ = writeln!(dot, "];");
+    }
+    for (a, b) in grid.iter_links() {
+        if let (Some(index_a), Some(index_b)) = (grid.grid_coordinate_to_index(a), grid.grid_coordinate_to_index(b)) {
+            let _ = writeln!(dot, "    {} -- {};", index_a, index_b);
+        }
+    }
+
+    let _ = writeln!(dot, "}}");
+    dot
+}
+
+/// Write the maze graph in the GraphML format.
+///
+/// Nodes have the ids `n<row major index>` and `x` and `y` data from their 2d coordinates, plus `distance` data if
+/// `distances` are given and the cell is reachable from the start.
+pub fn write_graphml<GridIndexType, CellT, Iters, MaxDistanceT>(
+    grid: &Grid<GridIndexType, CellT, Iters>,
+    distances: Option<&Distances<CellT, MaxDistanceT>>,
+) -> String
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+    MaxDistanceT: MaxDistance,
+{
+    let mut graphml = String::new();
+    let _ = writeln!(graphml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(graphml, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#);
+    let _ = writeln!(graphml, r#"  <key id="x" for="node" attr.name="x" attr.type="int"/>"#);
+    let _ = writeln!(graphml, r#"  <key id="y" for="node" attr.name="y" attr.type="int"/>"#);
+    if distances.is_some() {
+        let _ = writeln!(
+            graphml,
+            r#"  <key id="distance" for="node" attr.name="distance" attr.type="long"/>"#
+        );
+    }
+    let _ = writeln!(graphml, r#"  <graph id="maze" edgedefault="undirected">"#);
+
+    for cell in grid.iter() {
+        let index = grid
+            .grid_coordinate_to_index(cell)
+            .expect("Cell iter should give valid coordinate");
+        let c2d = cell.as_cartesian_2d();
+        let _ = write!(
+            graphml,
+            r#"    <node id="n{}"><data key="x">{}</data><data key="y">{}</data>"#,
+            index, c2d.x, c2d.y
+        );
+        if let Some(distance) = distances.and_then(|d| d.distance_from_start_to(cell)) {
+            let _ = write!(graphml, r#"<data key="distance">{}</data>"#, distance);
+        }
+        let _ = writeln!(graphml, "</node>");
+    }
+    for (a, b) in grid.iter_links() {
+        if let (Some(index_a), Some(index_b)) = (grid.grid_coordinate_to_index(a), grid.grid_coordinate_to_index(b)) {
+            let _ = writeln!(graphml, r#"    <edge source="n{}" target="n{}"/>"#, index_a, index_b);
+        }
+    }
+
+    let _ = writeln!(graphml, "  </graph>");
+    let _ = writeln!(graphml, "</graphml>");
+    graphml
+}
+
+/// The shape and size of a saved grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SavedDimensions {
+    Rectangular { width: usize, height: usize },
+    Polar { rows: usize },
+}
+
+/// Cells of grids that can be saved and loaded with serde.
+///
+/// The cell's name and the grid's dimensions are saved with the links, so a loaded grid is always rebuilt with the
+/// same shape it was saved with.
+pub trait SerializableCell: Cell + Sized {
+    /// The name of the cell type in saved grids.
+    const NAME: &'static str;
+    type Iters: GridIterators<Self>;
+
+    fn saved_dimensions(dimensions: &dyn GridDimensions) -> SavedDimensions;
+
+    /// A new grid without any links, `None` if the dimensions are the wrong shape for the cell type or too large for
+    /// the grid index type.
+    fn new_grid<GridIndexType: IndexType>(
+        dimensions: SavedDimensions,
+    ) -> Option<Grid<GridIndexType, Self, Self::Iters>>;
+}
+
+fn rect_saved_dimensions(dimensions: &dyn GridDimensions) -> SavedDimensions {
+    SavedDimensions::Rectangular {
+        width: dimensions.row_length(None).map_or(0, |RowLength(width)| width),
+        height: dimensions.column_length(None).0,
+    }
+}
+
+fn new_rect_grid<GridIndexType: IndexType, CellT: Cell>(
+    dimensions: SavedDimensions,
+) -> Option<Grid<GridIndexType, CellT, RectGridIterators>>
+where
+    RectGridIterators: GridIterators<CellT>,
+    RectGridCoordinates: GridCoordinates<CellT>,
+{
+    match dimensions {
+        SavedDimensions::Rectangular { width, height }
+            if width.checked_mul(height)? <= <GridIndexType as IndexType>::max().index() =>
+        {
+            Some(Grid::new(
+                Rc::new(RectGridDimensions::new(RowLength(width), ColumnLength(height))),
+                Box::new(RectGridCoordinates),
+                RectGridIterators,
+            ))
+        }
+        _ => None,
+    }
+}
+
+macro_rules! rect_serializable_cell {
+    ($cell:ty, $name:expr) => {
+        impl SerializableCell for $cell {
+            const NAME: &'static str = $name;
+            type Iters = RectGridIterators;
+
+            fn saved_dimensions(dimensions: &dyn GridDimensions) -> SavedDimensions {
+                rect_saved_dimensions(dimensions)
+            }
+
+            fn new_grid<GridIndexType: IndexType>(
+                dimensions: SavedDimensions,
+            ) -> Option<Grid<GridIndexType, Self, Self::Iters>> {
+                new_rect_grid(dimensions)
+            }
+        }
+    };
+}
+
+rect_serializable_cell!(SquareCell, "square");
+rect_serializable_cell!(HexCell, "hex");
+rect_serializable_cell!(TriangleCell, "triangle");
+rect_serializable_cell!(WeaveCell, "weave");
+
+impl SerializableCell for PolarCell {
+    const NAME: &'static str = "polar";
+    type Iters = PolarGridIterators;
+
+    fn saved_dimensions(dimensions: &dyn GridDimensions) -> SavedDimensions {
+        SavedDimensions::Polar {
+            rows: dimensions.rows().0,
+        }
+    }
+
+    fn new_grid<GridIndexType: IndexType>(
+        dimensions: SavedDimensions,
+    ) -> Option<Grid<GridIndexType, Self, Self::Iters>> {
+        match dimensions {
+            SavedDimensions::Polar { rows } => {
+                let polar_dimensions = PolarGridDimensions::new(RowsCount(rows));
+                if polar_dimensions.size().0 <= <GridIndexType as IndexType>::max().index() {
+                    Some(Grid::new(
+                        Rc::new(polar_dimensions),
+                        Box::new(PolarGridCoordinates),
+                        PolarGridIterators,
+                    ))
+                } else {
+                    None
+                }
+            }
+            SavedDimensions::Rectangular { .. } => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedCellWeight<Coord> {
+    cell: Coord,
+    weight: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedLinkWeight<Coord> {
+    a: Coord,
+    b: Coord,
+    weight: u32,
+}
+
+/// The saved form of a grid. Only weights that differ from the defaults are saved.
+#[derive(Serialize, Deserialize)]
+struct SavedGrid<Coord> {
+    format: String,
+    version: u32,
+    cell: String,
+    dimensions: SavedDimensions,
+    links: Vec<(Coord, Coord)>,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    cell_weights: Vec<SavedCellWeight<Coord>>,
+    #[serde(default = "Vec::new", skip_serializing_if = "Vec::is_empty")]
+    link_weights: Vec<SavedLinkWeight<Coord>>,
+}
+
+/// Grids are saved with a format name and version, the cell type, the dimensions, the links and any weights, e.g.
+/// `{"format":"mazes-grid","version":1,"cell":"square","dimensions":{"rectangular":{"width":2,"height":1}},
+/// "links":[[{"x":0,"y":0},{"x":1,"y":0}]]}`
+impl<GridIndexType, CellT, Iters> serde::Serialize for Grid<GridIndexType, CellT, Iters>
+where
+    GridIndexType: IndexType,
+    CellT: SerializableCell,
+    CellT::Coord: serde::Serialize,
+    Iters: GridIterators<CellT>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let cell_weights = self
+            .iter()
+            .filter_map(|cell| {
+                let weight = self.cell_weight(cell)?;
+                (weight != DEFAULT_CELL_WEIGHT).then_some(SavedCellWeight { cell, weight })
+            })
+            .collect();
+        let link_weights = self
+            .iter_links()
+            .filter_map(|(a, b)| {
+                let weight = self.link_weight(a, b)?;
+                (weight != DEFAULT_LINK_WEIGHT).then_some(SavedLinkWeight { a, b, weight })
+            })
+            .collect();
+
+        let saved = SavedGrid {
+            format: GRID_FORMAT.to_string(),
+            version: GRID_FORMAT_VERSION,
+            cell: CellT::NAME.to_string(),
+            dimensions: CellT::saved_dimensions(self.dimensions()),
+            links: self.iter_links().collect(),
+            cell_weights,
+            link_weights,
+        };
+        serde::Serialize::serialize(&saved, serializer)
+    }
+}
+
+/// Loads a grid saved by `Grid`'s `Serialize` implementation, checking the format, version, cell type and that every
+/// link and weight is for cells on the grid.
+impl<'de, GridIndexType, CellT> serde::Deserialize<'de> for Grid<GridIndexType, CellT, CellT::Iters>
+where
+    GridIndexType: IndexType,
+    CellT: SerializableCell,
+    CellT::Coord: serde::Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved: SavedGrid<CellT::Coord> = serde::Deserialize::deserialize(deserializer)?;
+        if saved.format != GRID_FORMAT {
+            return Err(D::Error::custom(format!(
+                "expected the {} format, found {}",
+                GRID_FORMAT, saved.format
+            )));
+        }
+        if saved.version != GRID_FORMAT_VERSION {
+            return Err(D::Error::custom(format!(
+                "unsupported {} version {}, expected version {}",
+                GRID_FORMAT, saved.version, GRID_FORMAT_VERSION
+            )));
+        }
+        if saved.cell != CellT::NAME {
+            return Err(D::Error::custom(format!(
+                "expected a grid of {} cells, found {} cells",
+                CellT::NAME,
+                saved.cell
+            )));
+        }
+
+        let mut grid = CellT::new_grid(saved.dimensions).ok_or_else(|| {
+            D::Error::custom(format!(
+                "{:?} are not valid dimensions for a grid of {} cells",
+                saved.dimensions,
+                CellT::NAME
+            ))
+        })?;
+        let links = saved.links.iter().map(|&(a, b)| ((a, b), a, b)).collect::<Vec<_>>();
+        link_neighbour_pairs(&mut grid, &links).map_err(|error| match error {
+            NeighbourLinkError::NotNeighbours((a, b)) => {
+                D::Error::custom(format!("cannot link {:?} to {:?} as they are not neighbours", a, b))
+            }
+            NeighbourLinkError::Link((a, b), CellLinkError::TooManyLinks) => D::Error::custom(format!(
+                "cannot link {:?} to {:?} as there are more links than the grid can count",
+                a, b
+            )),
+            NeighbourLinkError::Link((a, b), _) => D::Error::custom(format!("cannot link {:?} to {:?}", a, b)),
+        })?;
+        for SavedCellWeight { cell, weight } in saved.cell_weights {
+            if !grid.set_cell_weight(cell, weight) {
+                return Err(D::Error::custom(format!(
+                    "cell {:?} with a weight is not on the grid",
+                    cell
+                )));
+            }
+        }
+        for SavedLinkWeight { a, b, weight } in saved.link_weights {
+            if !grid.set_link_weight(a, b, weight) {
+                return Err(D::Error::custom(format!(
+                    "link from {:?} to {:?} with a weight is not in the maze",
+                    a, b
+                )));
+            }
+        }
+
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cells::Cartesian2DCoordinate,
+        generators,
+        grids::{polar_grid, small_rect_grid, weave_grid, PolarGrid, SmallRectangularGrid, WeaveGrid},
+        masks::BinaryMask2D,
+        renderers,
+        units::{ColumnLength, RowLength, RowsCount},
+    };
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn edges_round_trip() {
+        let mut g = small_rect_grid(RowLength(7), ColumnLength(5)).unwrap();
+        generators::wilson(&mut g, None, &mut SmallRng::seed_from_u64(8));
+        let edges = write_edges(&g);
+        assert!(edges.starts_with("35 34\n"));
+
+        let mut loaded = small_rect_grid(RowLength(7), ColumnLength(5)).unwrap();
+        read_edges(&mut loaded, &edges).unwrap();
+        assert_eq!(loaded.links_count(), g.links_count());
+        assert!(g.iter_links().all(|(a, b)| loaded.is_linked(a, b)));
+        assert_eq!(write_edges(&loaded), edges);
+    }
+
+    #[test]
+    fn reading_edges_checks_the_grid_and_each_edge() {
+        let read = |text: &str| {
+            let mut g = small_rect_grid(RowLength(3), ColumnLength(2)).unwrap();
+            read_edges(&mut g, text).map(|_| g)
+        };
+
+        let g = read("6 2\n1 2\n\n2 5\n").unwrap();
+        assert!(g.is_linked(Cartesian2DCoordinate::new(0, 0), Cartesian2DCoordinate::new(1, 0)));
+        assert!(g.is_linked(Cartesian2DCoordinate::new(1, 0), Cartesian2DCoordinate::new(1, 1)));
+
+        assert_eq!(read("").unwrap_err(), EdgesFormatError::MissingHeader);
+        assert_eq!(
+            read("9 0\n").unwrap_err(),
+            EdgesFormatError::VertexCountMismatch {
+                grid_cells: 6,
+                vertices: 9
+            }
+        );
+        assert_eq!(
+            read("6 2\n1 2\n").unwrap_err(),
+            EdgesFormatError::EdgeCountMismatch {
+                header_edges: 2,
+                edges: 1
+            }
+        );
+        assert_eq!(
+            read("6 1\n1 x\n").unwrap_err(),
+            EdgesFormatError::MalformedLine { line: 2 }
+        );
+        assert_eq!(
+            read("6 1\n1 2 3\n").unwrap_err(),
+            EdgesFormatError::MalformedLine { line: 2 }
+        );
+        assert_eq!(
+            read("6 1\n0 1\n").unwrap_err(),
+            EdgesFormatError::VertexOutOfRange { line: 2, vertex: 0 }
+        );
+        assert_eq!(
+            read("6 1\n6 7\n").unwrap_err(),
+            EdgesFormatError::VertexOutOfRange { line: 2, vertex: 7 }
+        );
+        // 3 is at the end of the first row and 4 is at the start of the second.
+        assert_eq!(
+            read("6 1\n3 4\n").unwrap_err(),
+            EdgesFormatError::NotNeighbours { line: 2, a: 3, b: 4 }
+        );
+        assert_eq!(
+            read("6 2\n1 2\n2 1\n").unwrap_err(),
+            EdgesFormatError::DuplicateEdge { line: 3, a: 2, b: 1 }
+        );
+        // The header's edge count is not used to allocate before the edges are read.
+        assert_eq!(
+            read("6 18446744073709551615\n1 2\n").unwrap_err(),
+            EdgesFormatError::EdgeCountMismatch {
+                header_edges: usize::MAX,
+                edges: 1
+            }
+        );
+    }
+
+    #[test]
+    fn reading_edges_checks_the_grid_can_count_them() {
+        // Every pair of neighbours on a 15x15 grid is more links than its u8 index type can count.
+        let mut g = small_rect_grid(RowLength(15), ColumnLength(15)).unwrap();
+        let rows = (0..15).flat_map(|y| (1..15).map(move |x| (y * 15 + x, y * 15 + x + 1)));
+        let columns = (1..=14 * 15).map(|vertex| (vertex, vertex + 15));
+        let edges = rows.chain(columns).collect::<Vec<(usize, usize)>>();
+        let text = edges.iter().fold(format!("225 {}\n", edges.len()), |text, (a, b)| {
+            text + &format!("{} {}\n", a, b)
+        });
+
+        assert_eq!(
+            read_edges(&mut g, &text).unwrap_err(),
+            EdgesFormatError::TooManyEdges {
+                edges: 420,
+                capacity: 255
+            }
+        );
+        assert_eq!(g.links_count(), 0);
+    }
+
+    #[test]
+    fn json_grid_round_trip() {
+        let mut g = small_rect_grid(RowLength(6), ColumnLength(4)).unwrap();
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(5));
+        let (a, b) = g.iter_links().next().unwrap();
+        g.set_cell_weight(a, 9);
+        g.set_link_weight(a, b, 4);
+
+        let json = serde_json::to_string(&g).unwrap();
+        let loaded: SmallRectangularGrid = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            (loaded.row_length(), loaded.column_length()),
+            (g.row_length(), g.column_length())
+        );
+        assert_eq!(loaded.links_count(), g.links_count());
+        assert!(g.iter_links().all(|(a, b)| loaded.is_linked(a, b)));
+        assert_eq!(loaded.cell_weight(a), Some(9));
+        assert_eq!(loaded.link_weight(a, b), Some(4));
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+
+        let mut polar = polar_grid(RowsCount(5)).unwrap();
+        generators::wilson(&mut polar, None, &mut SmallRng::seed_from_u64(5));
+        let loaded_polar: PolarGrid = serde_json::from_str(&serde_json::to_string(&polar).unwrap()).unwrap();
+        assert_eq!(loaded_polar.size(), polar.size());
+        assert!(polar.iter_links().all(|(a, b)| loaded_polar.is_linked(a, b)));
+    }
+
+    #[test]
+    fn loading_json_grid_checks_the_format() {
+        let load = |json: &str| serde_json::from_str::<SmallRectangularGrid>(json).map_err(|e| e.to_string());
+        let json = |version: u32, cell: &str, dimensions: &str, links: &str| {
+            format!(
+                r#"{{"format":"mazes-grid","version":{},"cell":"{}","dimensions":{},"links":{}}}"#,
+                version, cell, dimensions, links
+            )
+        };
+        let two_by_one = r#"{"rectangular":{"width":2,"height":1}}"#;
+        let link = r#"[[{"x":0,"y":0},{"x":1,"y":0}]]"#;
+
+        let g = load(&json(1, "square", two_by_one, link)).unwrap();
+        assert!(g.is_linked(Cartesian2DCoordinate::new(0, 0), Cartesian2DCoordinate::new(1, 0)));
+
+        assert!(load(&json(2, "square", two_by_one, link))
+            .unwrap_err()
+            .contains("version 2"));
+        assert!(load(&json(1, "hex", two_by_one, link))
+            .unwrap_err()
+            .contains("hex cells"));
+        assert!(load(&json(1, "square", r#"{"polar":{"rows":3}}"#, link)).is_err());
+        assert!(load(&json(1, "square", r#"{"rectangular":{"width":20,"height":20}}"#, link)).is_err());
+        assert!(
+            load(&json(1, "square", two_by_one, r#"[[{"x":0,"y":0},{"x":2,"y":0}]]"#))
+                .unwrap_err()
+                .contains("cannot link")
+        );
+        assert!(load(r#"{"format":"other","version":1}"#).is_err());
+    }
+
+    #[test]
+    fn loading_json_grid_checks_the_links() {
+        let load = |width: usize, links: &str| {
+            let json = format!(
+                r#"{{"format":"mazes-grid","version":1,"cell":"square","dimensions":{{"rectangular":{{"width":{},"height":{}}}}},"links":{}}}"#,
+                width, width, links
+            );
+            serde_json::from_str::<SmallRectangularGrid>(&json).map_err(|e| e.to_string())
+        };
+
+        let teleport = r#"[[{"x":0,"y":0},{"x":2,"y":2}]]"#;
+        assert!(load(3, teleport).unwrap_err().contains("not neighbours"));
+        // Square cells cannot tunnel under their neighbours.
+        let tunnel = r#"[[{"x":0,"y":1},{"x":1,"y":1}],[{"x":1,"y":0},{"x":1,"y":1}],[{"x":1,"y":1},{"x":1,"y":2}],[{"x":0,"y":1},{"x":2,"y":1}]]"#;
+        assert!(load(3, tunnel).unwrap_err().contains("not neighbours"));
+
+        // Every pair of neighbours on a 15x15 grid is more links than its u8 index type can count.
+        let all_neighbours = small_rect_grid(RowLength(15), ColumnLength(15)).unwrap();
+        let links = all_neighbours
+            .iter()
+            .flat_map(|a| all_neighbours.neighbours(a).into_iter().map(move |b| (a, b)))
+            .filter(|(a, b)| a < b)
+            .collect::<Vec<_>>();
+        assert!(load(15, &serde_json::to_string(&links).unwrap())
+            .unwrap_err()
+            .contains("more links than the grid can count"));
+    }
+
+    #[test]
+    fn weave_tunnels_load_in_any_order() {
+        let mut g = weave_grid(RowLength(12), ColumnLength(12)).unwrap();
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(3));
+        let is_tunnel =
+            |(a, b): &(Cartesian2DCoordinate, Cartesian2DCoordinate)| a.x.abs_diff(b.x) + a.y.abs_diff(b.y) > 1;
+        assert!(g.iter_links().any(|link| is_tunnel(&link)));
+
+        // The tunnels come before the corridors they pass under.
+        let mut links = g.iter_links().collect::<Vec<_>>();
+        links.sort_by_key(|link| !is_tunnel(link));
+        let edges = links
+            .iter()
+            .fold(format!("{} {}\n", g.size(), links.len()), |text, (a, b)| {
+                let index = |c| g.grid_coordinate_to_index(c).unwrap() + 1;
+                text + &format!("{} {}\n", index(*a), index(*b))
+            });
+        let mut from_edges = weave_grid(RowLength(12), ColumnLength(12)).unwrap();
+        read_edges(&mut from_edges, &edges).unwrap();
+        assert_eq!(from_edges.links_count(), g.links_count());
+        assert!(g.iter_links().all(|(a, b)| from_edges.is_linked(a, b)));
+
+        let from_json: WeaveGrid = serde_json::from_str(&serde_json::to_string(&g).unwrap()).unwrap();
+        assert!(g.iter_links().all(|(a, b)| from_json.is_linked(a, b)));
+
+        // A tunnel that would pass under a cell that is not a corridor across it is rejected, leaving the grid as it was.
+        let mut blocked = weave_grid(RowLength(3), ColumnLength(1)).unwrap();
+        assert_eq!(
+            read_edges(&mut blocked, "3 2\n1 2\n1 3\n").unwrap_err(),
+            EdgesFormatError::NotNeighbours { line: 3, a: 1, b: 3 }
+        );
+        assert_eq!(blocked.links_count(), 0);
+    }
+
+    #[test]
+    fn json_mask_round_trip() {
+        let mask: BinaryMask2D = serde_json::from_str(r#"{"width":3,"height":2,"masked":[1,5]}"#).unwrap();
+        assert!(mask.is_masked(Cartesian2DCoordinate::new(1, 0)));
+        assert!(mask.is_masked(Cartesian2DCoordinate::new(2, 1)));
+        assert!(!mask.is_masked(Cartesian2DCoordinate::new(0, 0)));
+        assert_eq!(
+            serde_json::to_string(&mask).unwrap(),
+            r#"{"width":3,"height":2,"masked":[1,5]}"#
+        );
+        assert!(serde_json::from_str::<BinaryMask2D>(r#"{"width":3,"height":2,"masked":[6]}"#).is_err());
+    }
+
+    #[test]
+    fn dot_and_graphml_have_positions_links_and_distances() {
+        // 0 - 1
+        //     |
+        // 2   3
+        let mut g = small_rect_grid(RowLength(2), ColumnLength(2)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(0, 0), gc(1, 0)).unwrap();
+        g.link(gc(1, 0), gc(1, 1)).unwrap();
+        let distances = Distances::<SquareCell, u32>::for_grid(&g, gc(0, 0)).unwrap();
+
+        let dot = write_dot(&g, None::<&Distances<SquareCell>>);
+        assert!(dot.starts_with("graph maze {\n    node [shape=point];\n"));
+        assert!(dot.contains("    3 [pos=\"1,-1!\"];\n"));
+        assert!(dot.contains("    0 -- 1;\n"));
+        assert!(dot.contains("    1 -- 3;\n"));
+        assert!(dot.ends_with("}\n"));
+        let dot = write_dot(&g, Some(&distances));
+        assert!(dot.contains("    3 [pos=\"1,-1!\", label=\"2\"];\n"));
+        assert!(dot.contains("    2 [pos=\"0,-1!\"];\n"));
+        assert!(dot.contains("    0 [pos=\"0,0!\", label=\"0\"];\n"));
+
+        let graphml = write_graphml(&g, Some(&distances));
+        assert!(graphml.contains(
+            r#"<node id="n3"><data key="x">1</data><data key="y">1</data><data key="distance">2</data></node>"#
+        ));
+        assert!(graphml.contains(r#"<node id="n2"><data key="x">0</data><data key="y">1</data></node>"#));
+        assert!(graphml.contains(r#"<edge source="n1" target="n3"/>"#));
+        assert_eq!(graphml.matches("<edge ").count(), 2);
+        assert!(!write_graphml(&g, None::<&Distances<SquareCell>>).contains("distance"));
+    }
+
+    #[test]
+    fn text_maze_round_trip() {
+        let mut g = small_rect_grid(RowLength(9), ColumnLength(6)).unwrap();
+        generators::hunt_and_kill(&mut g, None, &mut SmallRng::seed_from_u64(4));
+        generators::braid(&mut g, 0.5, &mut SmallRng::seed_from_u64(4));
+        let distances = Distances::<SquareCell, u32>::for_grid(&g, Cartesian2DCoordinate::new(0, 0)).unwrap();
+        g.set_grid_display(Some(Rc::new(distances)));
+        let text = format!("{}", g);
+
+        let loaded: SmallRectangularGrid = read_text_maze(&format!("\n{}\n", text)).unwrap();
+        assert_eq!(
+            (loaded.row_length(), loaded.column_length()),
+            (g.row_length(), g.column_length())
+        );
+        assert_eq!(loaded.links_count(), g.links_count());
+        assert!(g.iter_links().all(|(a, b)| loaded.is_linked(a, b)));
+    }
+
+    #[test]
+    fn reading_ascii_text_maze_and_its_errors() {
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        let ascii = "+---+---+\n| S     |\n+---+   +\n| E     |\n+---+---+\n";
+        let g: SmallRectangularGrid = read_text_maze(ascii).unwrap();
+        assert_eq!(g.size(), 4);
+        assert_eq!(g.links_count(), 3);
+        assert!(g.is_linked(gc(0, 0), gc(1, 0)));
+        assert!(g.is_linked(gc(1, 0), gc(1, 1)));
+        assert!(g.is_linked(gc(1, 1), gc(0, 1)));
+
+        let read = |text: &str| read_text_maze::<u8>(text).unwrap_err();
+        assert_eq!(read(" \n"), TextMazeError::Empty);
+        assert_eq!(read("+---+\n|   |\n"), TextMazeError::LineCount { lines: 2 });
+        assert_eq!(read("+--+\n|  |\n+--+"), TextMazeError::LineWidth { line: 1, width: 4 });
+        assert_eq!(
+            read("+---+\n|  |\n+---+"),
+            TextMazeError::UnevenLine {
+                line: 2,
+                width: 4,
+                expected_width: 5
+            }
+        );
+        assert_eq!(
+            read("+---+\n|    \n+---+"),
+            TextMazeError::OpenBoundary { line: 2, column: 5 }
+        );
+        assert_eq!(
+            read("+-x-+\n|   |\n+---+"),
+            TextMazeError::UnexpectedCharacter {
+                line: 1,
+                column: 3,
+                character: 'x'
+            }
+        );
+        assert_eq!(
+            read("+---+---+\n|   |   |\n+-- +---+"),
+            TextMazeError::UnexpectedCharacter {
+                line: 3,
+                column: 4,
+                character: ' '
+            }
+        );
+        let too_large = format!(
+            "+{}\n|{}|\n+{}",
+            "---+".repeat(300),
+            "    ".repeat(299) + "   ",
+            "---+".repeat(300)
+        );
+        assert_eq!(read(&too_large), TextMazeError::TooLarge { width: 300, height: 1 });
+    }
+
+    #[test]
+    fn image_maze_round_trip() {
+        let mut g = small_rect_grid(RowLength(8), ColumnLength(5)).unwrap();
+        generators::recursive_backtracker(&mut g, None, &mut SmallRng::seed_from_u64(6));
+        generators::braid(&mut g, 0.3, &mut SmallRng::seed_from_u64(6));
+        let start = Cartesian2DCoordinate::new(0, 0);
+        let end = Cartesian2DCoordinate::new(7, 4);
+        let distances = Distances::<SquareCell, u32>::for_grid(&g, start).unwrap();
+        let options = renderers::RenderOptionsBuilder::new()
+            .cell_side_pixels_length(12)
+            .colour_distances(true)
+            .mark_start_end(true)
+            .start(Some(start))
+            .end(Some(end))
+            .show_path(true)
+            .distances(Some(&distances))
+            .path(crate::pathing::shortest_path(&g, &distances, end))
+            .build();
+        let image = DynamicImage::ImageRgb8(renderers::square_grid_image(&g, &options));
+
+        let same_maze = |loaded: &SmallRectangularGrid| {
+            loaded.row_length() == g.row_length()
+                && loaded.column_length() == g.column_length()
+                && loaded.links_count() == g.links_count()
+                && g.iter_links().all(|(a, b)| loaded.is_linked(a, b))
+        };
+        assert!(same_maze(&read_image_maze(&image, None).unwrap()));
+        assert!(same_maze(&read_image_maze(&image, Some(12)).unwrap()));
+
+        // Like a scan: black walls two pixels thick and no extra pixel for the south and east walls.
+        let rendered = image.to_rgb8();
+        let scanned = RgbImage::from_fn(8 * 12, 5 * 12, |x, y| {
+            let is_wall = |x: u32| *rendered.get_pixel(x, y) == Rgb([0, 0, 0xff]);
+            if is_wall(x) || (x > 0 && is_wall(x - 1)) {
+                Rgb([20, 20, 20])
+            } else {
+                Rgb([0xf0, 0xf0, 0xf0])
+            }
+        });
+        assert!(same_maze(
+            &read_image_maze(&DynamicImage::ImageRgb8(scanned), Some(12)).unwrap()
+        ));
+    }
+
+    #[test]
+    fn reading_image_maze_errors() {
+        let blank = |w, h| DynamicImage::ImageRgb8(RgbImage::from_pixel(w, h, Rgb([0xff, 0xff, 0xff])));
+        let read = |image: &DynamicImage, cell_pixels| read_image_maze::<u8>(image, cell_pixels).unwrap_err();
+        assert_eq!(
+            read(&blank(21, 21), Some(2)),
+            ImageMazeError::CellTooSmall { cell_pixels: 2 }
+        );
+        assert_eq!(
+            read(&blank(4, 21), Some(10)),
+            ImageMazeError::NoCells {
+                image_width: 4,
+                image_height: 21
+            }
+        );
+        assert_eq!(
+            read(&blank(401, 401), Some(10)),
+            ImageMazeError::TooLarge { width: 40, height: 40 }
+        );
+        // Every pixel is the wall colour so no cell size fits.
+        assert_eq!(read(&blank(21, 21), None), ImageMazeError::UnknownCellSize);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{
+    cells::{Cell, Coordinate},
+    grid::{Grid, IndexType},
+    grid_traits::GridIterators,
+    masks::BinaryMask2D,
+    pathing,
+};
+
+use petgraph::unionfind::UnionFind;
+use serde_derive::Serialize;
+use std::fmt;
+
+/// Texture measurements of a maze, for comparing how generators shape their mazes.
+///
+/// Only unmasked cells are measured. Passage directions come from `Coordinate::as_cartesian_2d`, so the corridor and
+/// bias measurements are only meaningful for square (and weave) grids.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MazeStats {
+    /// Unmasked cells.
+    pub cells: usize,
+    /// Passages between cells.
+    pub links: usize,
+    /// Cells with exactly one passage.
+    pub dead_ends: usize,
+    pub dead_end_percentage: f64,
+    /// Cells with three or more passages.
+    pub junctions: usize,
+    /// The number of cells with each number of passages, indexed by passage count.
+    pub link_degree_counts: Vec<usize>,
+    /// Steps along the longest path, exact for perfect mazes and an estimate for mazes with loops.
+    pub longest_path_length: usize,
+    /// The mean number of passages in a straight run of passages, east-west or north-south.
+    pub average_corridor_length: f64,
+    /// The mean number of cells walked from a dead end to the first junction. Mazes with a high river factor have
+    /// fewer, longer dead ends that flow like rivers, rather than many short stubs.
+    pub river_factor: f64,
+    /// East-west passages.
+    pub horizontal_passages: usize,
+    /// North-south passages.
+    pub vertical_passages: usize,
+    /// -1.0 for only north-south passages, up to 1.0 for only east-west passages.
+    pub passage_bias: f64,
+    /// Separate areas of the maze that cannot reach each other, 1 for a connected maze.
+    pub connected_components: usize,
+}
+
+impl MazeStats {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Maze stats are always serializable.")
+    }
+}
+
+impl fmt::Display for MazeStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Cells: {}", self.cells)?;
+        writeln!(f, "Links: {}", self.links)?;
+        writeln!(f, "Dead ends: {} ({:.1}%)", self.dead_ends, self.dead_end_percentage)?;
+        writeln!(f, "Junctions: {}", self.junctions)?;
+        writeln!(f, "Cells by link count: {:?}", self.link_degree_counts)?;
+        writeln!(f, "Longest path length: {}", self.longest_path_length)?;
+        writeln!(f, "Average corridor length: {:.2}", self.average_corridor_length)?;
+        writeln!(f, "River factor: {:.2}", self.river_factor)?;
+        writeln!(
+            f,
+            "Horizontal/vertical passages: {}/{} (bias {:.2})",
+            self.horizontal_passages, self.vertical_passages, self.passage_bias
+        )?;
+        write!(f, "Connected components: {}", self.connected_components)
+    }
+}
+
+/// Measure the texture of the maze on the grid, ignoring any masked cells.
+pub fn maze_stats<GridIndexType, CellT, Iters>(
+    grid: &Grid<GridIndexType, CellT, Iters>,
+    mask: Option<&BinaryMask2D>,
+) -> MazeStats
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    let is_unmasked = |c: CellT::Coord| !mask.is_some_and(|m| m.is_masked(c));
+    let cells: Vec<CellT::Coord> = grid.iter().filter(|c| is_unmasked(*c)).collect();
+    let degree = |c: CellT::Coord| grid.links(c).map_or(0, |links| links.len());
+
+    let mut link_degree_counts = vec![];
+    for &cell in &cells {
+        let cell_degree = degree(cell);
+        if cell_degree >= link_degree_counts.len() {
+            link_degree_counts.resize(cell_degree + 1, 0);
+        }
+        link_degree_counts[cell_degree] += 1;
+    }
+    let dead_ends = link_degree_counts.get(1).cloned().unwrap_or(0);
+    let junctions = link_degree_counts.iter().skip(3).sum();
+
+    let longest_path_length =
+        pathing::dijkstra_longest_path::<_, u32, _, _>(grid, mask).map_or(0, |path| path.len().saturating_sub(1));
+
+    // Passages are horizontal when both cells are on the same row.
+    let is_horizontal = |a: CellT::Coord, b: CellT::Coord| a.as_cartesian_2d().y == b.as_cartesian_2d().y;
+    let (mut horizontal_passages, mut vertical_passages) = (0, 0);
+    for (a, b) in grid.iter_links() {
+        if is_horizontal(a, b) {
+            horizontal_passages += 1;
+        } else {
+            vertical_passages += 1;
+        }
+    }
+    let links = horizontal_passages + vertical_passages;
+
+    // Each straight run of passages starts at its west most or north most cell.
+    let corridors_count = cells
+        .iter()
+        .map(|&cell| {
+            let c2d = cell.as_cartesian_2d();
+            let links = grid.links(cell).expect("Maze cell has an invalid cell coordinate.");
+            let linked_towards = |horizontal: bool, before: bool| {
+                links.iter().any(|&link| {
+                    let l2d = link.as_cartesian_2d();
+                    is_horizontal(cell, link) == horizontal
+                        && if horizontal {
+                            (l2d.x < c2d.x) == before
+                        } else {
+                            (l2d.y < c2d.y) == before
+                        }
+                })
+            };
+            [true, false]
+                .iter()
+                .filter(|&&horizontal| linked_towards(horizontal, false) && !linked_towards(horizontal, true))
+                .count()
+        })
+        .sum::<usize>();
+
+    let mut connected_sets = UnionFind::<usize>::new(grid.size());
+    for (a, b) in grid.iter_links() {
+        if let (Some(a_index), Some(b_index)) = (grid.grid_coordinate_to_index(a), grid.grid_coordinate_to_index(b)) {
+            connected_sets.union(a_index, b_index);
+        }
+    }
+    let mut component_roots = cells
+        .iter()
+        .filter_map(|&c| grid.grid_coordinate_to_index(c))
+        .map(|index| connected_sets.find(index))
+        .collect::<Vec<_>>();
+    component_roots.sort_unstable();
+    component_roots.dedup();
+
+    let dead_end_branch_cells: usize = cells
+        .iter()
+        .filter(|&&c| degree(c) == 1)
+        .map(|&dead_end| dead_end_branch_length(grid, dead_end))
+        .sum();
+
+    let ratio = |numerator: usize, denominator: usize| {
+        if denominator == 0 {
+            0.0
+        } else {
+            numerator as f64 / denominator as f64
+        }
+    };
+
+    MazeStats {
+        cells: cells.len(),
+        links,
+        dead_ends,
+        dead_end_percentage: 100.0 * ratio(dead_ends, cells.len()),
+        junctions,
+        link_degree_counts,
+        longest_path_length,
+        average_corridor_length: ratio(links, corridors_count),
+        river_factor: ratio(dead_end_branch_cells, dead_ends),
+        horizontal_passages,
+        vertical_passages,
+        passage_bias: ratio(horizontal_passages, links) - ratio(vertical_passages, links),
+        connected_components: component_roots.len(),
+    }
+}
+
+/// Count the cells from a dead end up to, but not including, the first junction.
+/// A maze that is a single corridor has no junctions so the whole corridor is counted.
+fn dead_end_branch_length<GridIndexType, CellT, Iters>(
+    grid: &Grid<GridIndexType, CellT, Iters>,
+    dead_end: CellT::Coord,
+) -> usize
+where
+    GridIndexType: IndexType,
+    CellT: Cell,
+    Iters: GridIterators<CellT>,
+{
+    let mut previous = None;
+    let mut current = dead_end;
+    let mut length = 0;
+    loop {
+        let links = grid.links(current).expect("Maze cell has an invalid cell coordinate.");
+        if links.len() > 2 {
+            return length;
+        }
+        length += 1;
+        match links.iter().find(|&&link| Some(link) != previous) {
+            Some(&next) if next != dead_end => {
+                previous = Some(current);
+                current = next;
+            }
+            _ => return length,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cells::Cartesian2DCoordinate,
+        generators,
+        grids::small_rect_grid,
+        units::{ColumnLength, RowLength},
+    };
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn stats_of_a_hand_made_maze() {
+        // 0 - 1 - 2
+        //     |
+        // 3 - 4   5
+        let mut g = small_rect_grid(RowLength(3), ColumnLength(2)).unwrap();
+        let gc = |x, y| Cartesian2DCoordinate::new(x, y);
+        g.link(gc(0, 0), gc(1, 0)).unwrap();
+        g.link(gc(1, 0), gc(2, 0)).unwrap();
+        g.link(gc(1, 0), gc(1, 1)).unwrap();
+        g.link(gc(0, 1), gc(1, 1)).unwrap();
+
+        let stats = maze_stats(&g, None);
+        assert_eq!(stats.cells, 6);
+        assert_eq!(stats.links, 4);
+        assert_eq!(stats.link_degree_counts, vec![1, 3, 1, 1]);
+        assert_eq!(stats.dead_ends, 3);
+        assert_eq!(stats.dead_end_percentage, 50.0);
+        assert_eq!(stats.junctions, 1);
+        assert_eq!(stats.longest_path_length, 3);
+        // Runs: 0-1-2, 3-4 and 1-4.
+        assert_eq!(stats.average_corridor_length, 4.0 / 3.0);
+        // Branches: 0, 2 and 3-4.
+        assert_eq!(stats.river_factor, 4.0 / 3.0);
+        assert_eq!((stats.horizontal_passages, stats.vertical_passages), (3, 1));
+        assert_eq!(stats.passage_bias, 0.5);
+        // Cell 5 is cut off.
+        assert_eq!(stats.connected_components, 2);
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+        assert_eq!(json["dead_ends"], 3);
+        assert_eq!(json["link_degree_counts"], serde_json::json!([1, 3, 1, 1]));
+    }
+
+    #[test]
+    fn recursive_backtracker_has_fewer_longer_dead_ends_than_prims() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut backtracker_grid = small_rect_grid(RowLength(12), ColumnLength(12)).unwrap();
+        generators::recursive_backtracker(&mut backtracker_grid, None, &mut rng);
+        let mut prims_grid = small_rect_grid(RowLength(12), ColumnLength(12)).unwrap();
+        generators::true_prims(&mut prims_grid, None, &mut rng);
+        let backtracker = maze_stats(&backtracker_grid, None);
+        let prims = maze_stats(&prims_grid, None);
+
+        for stats in [&backtracker, &prims] {
+            assert_eq!(stats.connected_components, 1);
+            assert_eq!(stats.links, stats.cells - 1);
+            assert_eq!(stats.link_degree_counts.iter().sum::<usize>(), stats.cells);
+        }
+        assert!(backtracker.river_factor > prims.river_factor);
+        assert!(backtracker.dead_ends < prims.dead_ends);
+        assert!(backtracker.longest_path_length > prims.longest_path_length);
+    }
+}